@@ -1,212 +1,642 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use console::Style;
-use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use dashmap::{DashMap, DashSet};
 use indicatif::{ProgressBar, ProgressStyle};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
 use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
-use std::sync::Arc;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
-use tokio::time::sleep;
+use tokio::time::{interval_at, sleep};
 
-use crate::geyser_client::{GeyserStreamClient, GeyserTransaction};
-use crate::shredlink_client::{ShredlinkClient, ShredlinkTransaction};
+use crate::histogram::{self, LatencyHistogram};
+use crate::leaders::{LeaderBreakdown, LeaderSchedule, LeaderTotals, TOP_LEADERS};
+use crate::metrics::Metrics;
+use crate::config::Commitment;
+use crate::slots::{SlotAnalysis, SlotArrivals, SlotStatusAnalysis, SlotTotals, SlotUpdateTotals};
+use crate::reconnect::{run_with_reconnect, ReconnectPolicy, SourceHealth};
+use crate::report::{self, ObservationWriter, ReportFormat};
+use crate::source::{Observation, RunEpoch, SourceEvent, StreamSource};
+use crate::stats::{self, ConfidenceInterval};
+use crate::status;
 
-/// Stores timestamps for a transaction from multiple Geyser sources and Shredlink
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Stores timestamps for a transaction from every source that reported it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TransactionTimestamp {
-    pub geyser_timestamps: HashMap<String, u64>, // geyser_name -> timestamp
-    pub shredlink_timestamp: Option<u64>,
+    pub timestamps: HashMap<String, u64>, // source_name -> receive timestamp (ns since run epoch)
+    pub slot: Option<u64>, // slot reported by the first source
+    #[serde(default)]
+    pub slots: HashMap<String, u64>, // source_name -> slot it reported
 }
 
 impl TransactionTimestamp {
     pub fn new() -> Self {
-        Self {
-            geyser_timestamps: HashMap::new(),
-            shredlink_timestamp: None,
-        }
+        Self::default()
     }
     
-    /// Calculate latency difference against each Geyser source (positive means Geyser is faster)
-    pub fn latency_diffs_ms(&self) -> HashMap<String, i64> {
-        let mut diffs = HashMap::new();
-        
-        if let Some(shredlink_ts) = self.shredlink_timestamp {
-            for (geyser_name, geyser_ts) in &self.geyser_timestamps {
-                let diff = *geyser_ts as i64 - shredlink_ts as i64;
-                diffs.insert(geyser_name.clone(), diff);
-            }
-        }
-        
-        diffs
+    /// Calculate latency difference of each source against the baseline (positive means the baseline is faster)
+    pub fn latency_diffs_ns(&self, baseline: &str) -> HashMap<String, i64> {
+        self.timestamps
+            .keys()
+            .filter(|source_name| *source_name != baseline)
+            .filter_map(|source_name| Some((source_name.clone(), self.latency_diff_ns(baseline, source_name)?)))
+            .collect()
+    }
+    
+    /// Arrival difference between two sources (positive means `first` is faster)
+    pub fn latency_diff_ns(&self, first: &str, second: &str) -> Option<i64> {
+        let first_ts = *self.timestamps.get(first)?;
+        let second_ts = *self.timestamps.get(second)?;
+        Some(second_ts as i64 - first_ts as i64)
+    }
+    
+    pub fn has_source(&self, source_name: &str) -> bool {
+        self.timestamps.contains_key(source_name)
     }
     
-    pub fn has_any_geyser(&self) -> bool {
-        !self.geyser_timestamps.is_empty()
+    /// Earliest receive timestamp over all sources
+    pub fn first_arrival(&self) -> Option<u64> {
+        self.timestamps.values().min().copied()
     }
 }
 
-/// Benchmark results summary for multiple Geyser sources vs Shredlink
+/// Benchmark results summary for every source measured against the baseline
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BenchmarkReport {
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
     pub duration_seconds: f64,
+    /// Time spent warming up before measurement started
+    pub warmup_seconds: f64,
+    pub interrupted: bool,
     pub total_transactions: usize,
-    pub shredlink_only_count: usize,
-    pub geyser_results: HashMap<String, GeyserStats>,
+    pub baseline: String,
+    pub baseline_only_count: usize,
+    /// Transactions delivered only by sources that cannot drop failed ones, left out of only-seen-by and missed counts
+    #[serde(default)]
+    pub possibly_failed_count: usize,
+    /// Time other sources get to deliver a transaction after its first arrival
+    pub match_window_seconds: f64,
+    /// Transactions another source delivered but this one had not within the match window (outages excluded)
+    pub missed: HashMap<String, usize>,
+    /// Arrival differences up to this size count as ties
+    pub tie_tolerance_ms: f64,
+    pub source_results: HashMap<String, GeyserStats>,
+    /// Every ordered pair of sources, sorted by row then column
+    pub pairwise: Vec<PairStats>,
+    pub leaderboard: Leaderboard,
+    pub slots: SlotAnalysis,
+    /// Per slot status, when each source with slot updates announced it; empty without Geyser sources
+    #[serde(default)]
+    pub slot_updates: Vec<SlotStatusAnalysis>,
+    /// Only with a leader schedule
+    pub leaders: Option<LeaderBreakdown>,
+    pub connection_stats: HashMap<String, ConnectionStats>,
 }
 
+/// One cell of the pairwise matrix: how the row source compares with the column source
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PairStats {
+    pub row: String,
+    pub column: String,
+    pub matched_transactions: usize,
+    /// Column arrival minus row arrival (positive means the row source is faster)
+    pub median_diff_ms: f64,
+    pub p95_diff_ms: f64,
+    pub row_wins: usize,
+    pub ties: usize,
+    pub row_losses: usize,
+    pub row_wins_percentage: f64,
+    pub sign_test_p_value: f64,
+    pub wilcoxon_p_value: f64,
+}
+
+/// Which source delivers first, over transactions seen by at least `quorum` sources
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub quorum: usize,
+    pub transactions: usize,
+    /// Ranked by first-arrival share, then by median lag
+    pub sources: Vec<LeaderboardEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub source: String,
+    /// Quorum transactions this source delivered
+    pub seen: usize,
+    /// Quorum transactions this source delivered first (within the tie tolerance, so ties credit every tied source)
+    pub first_arrivals: usize,
+    pub first_arrival_percentage: f64,
+    /// Lag behind the earliest arrival, over the quorum transactions this source delivered
+    pub median_lag_ms: f64,
+    pub p95_lag_ms: f64,
+    pub p99_lag_ms: f64,
+}
+
+/// Connection stability of one source over the run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    pub disconnects: u64,
+    pub downtime_seconds: f64,
+}
+
+/// Latency statistics for one source compared with the baseline
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GeyserStats {
     pub matched_transactions: usize,
-    pub geyser_only_count: usize,
+    pub source_only_count: usize,
     pub average_latency_ms: f64,
     pub median_latency_ms: f64,
     pub p95_latency_ms: f64,
     pub p99_latency_ms: f64,
-    pub min_latency_ms: i64,
-    pub max_latency_ms: i64,
-    pub shredlink_wins_percentage: f64,
+    pub min_latency_ms: f64,
+    pub max_latency_ms: f64,
+    pub average_latency_us: f64,
+    pub median_latency_us: f64,
+    pub p95_latency_us: f64,
+    pub p99_latency_us: f64,
+    pub min_latency_us: f64,
+    pub max_latency_us: f64,
+    /// Matched transactions the baseline delivered earlier by more than the tie tolerance
+    pub baseline_wins: usize,
+    pub ties: usize,
+    pub baseline_losses: usize,
+    pub baseline_wins_percentage: f64,
+    /// 95% bootstrap intervals of the median and p95 difference
+    pub median_latency_ci_ms: ConfidenceInterval,
+    pub p95_latency_ci_ms: ConfidenceInterval,
+    /// 95% Wilson interval of the baseline win rate
+    pub baseline_wins_ci_percentage: ConfidenceInterval,
+    /// Paired tests of "neither source is faster"; ties are excluded
+    pub sign_test_p_value: f64,
+    pub wilcoxon_p_value: f64,
+}
+
+/// Warm-up during which sources are connected but observations are discarded
+#[derive(Debug, Clone, Copy)]
+pub struct Warmup {
+    /// Minimum warm-up time
+    pub duration: Duration,
+    /// Start measuring after this long even if some source has not delivered anything yet
+    pub timeout: Duration,
+}
+
+/// Decides which observations are measured; everything received before it opens is discarded
+#[derive(Default)]
+struct MeasurementGate {
+    /// Receive time (ns since run epoch) from which observations count
+    opened_at: OnceLock<u64>,
+    ready_sources: DashSet<String>,
+    warmup_signatures: DashSet<String>,
+    /// Evicted transactions -> eviction time, so late arrivals do not start them over
+    finalized_signatures: DashMap<String, u64>,
+}
+
+impl MeasurementGate {
+    fn open(&self, now_ns: u64) {
+        let _ = self.opened_at.set(now_ns);
+    }
+    
+    fn is_measuring(&self, received_at: u64) -> bool {
+        self.opened_at.get().is_some_and(|&opened_at| received_at >= opened_at)
+    }
+    
+    /// Whether the observation counts; transactions first seen during warm-up never do
+    fn admit(&self, observation: &Observation) -> bool {
+        if !self.ready_sources.contains(&observation.source) {
+            self.ready_sources.insert(observation.source.clone());
+        }
+        
+        if !self.is_measuring(observation.received_at) {
+            self.warmup_signatures.insert(observation.signature.clone());
+            return false;
+        }
+        !self.warmup_signatures.contains(&observation.signature)
+            && !self.finalized_signatures.contains_key(&observation.signature)
+    }
+}
+
+/// Report counts of every evicted transaction; they are folded in before the entry is dropped
+#[derive(Debug, Clone, Default)]
+struct RunningTotals {
+    transactions: usize,
+    baseline_only: usize,
+    possibly_failed: usize,
+    source_only: HashMap<String, usize>,
+    missed: HashMap<String, usize>,
+    /// Transactions seen by at least `quorum` sources
+    quorum_transactions: usize,
+    first_arrivals: HashMap<String, usize>,
+    /// Lag behind the earliest arrival on quorum transactions
+    lags: HashMap<String, LatencyHistogram>,
+    slots: SlotTotals,
+    slot_updates: SlotUpdateTotals,
+    leaders: LeaderTotals,
+}
+
+impl RunningTotals {
+    fn merge(&mut self, other: &RunningTotals) {
+        self.transactions += other.transactions;
+        self.baseline_only += other.baseline_only;
+        self.possibly_failed += other.possibly_failed;
+        self.quorum_transactions += other.quorum_transactions;
+        merge_counts(&mut self.source_only, &other.source_only);
+        merge_counts(&mut self.missed, &other.missed);
+        merge_counts(&mut self.first_arrivals, &other.first_arrivals);
+        merge_histograms(&mut self.lags, &other.lags);
+        self.slots.merge(&other.slots);
+        self.slot_updates.merge(&other.slot_updates);
+        self.leaders.merge(&other.leaders);
+    }
+}
+
+/// Everything a report is computed from
+#[derive(Debug, Clone, Default)]
+struct Aggregates {
+    /// (first, second) -> distribution of second's arrival minus first's
+    pairs: HashMap<(String, String), LatencyHistogram>,
+    totals: RunningTotals,
+}
+
+impl Aggregates {
+    fn merge(&mut self, other: &Aggregates) {
+        merge_histograms(&mut self.pairs, &other.pairs);
+        self.totals.merge(&other.totals);
+    }
+}
+
+/// Rolling-window reports emitted while running indefinitely
+pub struct DaemonReports {
+    pub interval: Duration,
+    pub format: ReportFormat,
+    /// Reports are appended to this file, or printed when unset
+    pub output: Option<PathBuf>,
 }
 
 pub struct Benchmark {
-    geyser_urls: HashMap<String, String>, // geyser_name -> url
-    geyser_tokens: HashMap<String, String>, // geyser_name -> token
-    shredlink_url: String,
+    sources: Vec<Box<dyn StreamSource>>,
+    source_names: Vec<String>,
+    baseline: String,
+    reconnect_policy: ReconnectPolicy,
     transactions: Arc<DashMap<String, TransactionTimestamp>>,
+    /// (first, second) -> distribution of second's arrival minus first's, recorded as matches happen
+    pair_histograms: Arc<DashMap<(String, String), LatencyHistogram>>,
+    slot_arrivals: Arc<SlotArrivals>,
+    /// First announcement of each (slot, status) by each source
+    slot_updates: Arc<SlotArrivals<(u64, Commitment)>>,
+    /// Transactions seen by the baseline and at least one other source
+    matched: Arc<AtomicUsize>,
+    source_health: Arc<DashMap<String, SourceHealth>>,
+    gate: Arc<MeasurementGate>,
+    warmup: Option<Warmup>,
+    quorum: usize,
+    tie_tolerance_ns: i64,
+    match_window: Duration,
+    totals: Mutex<RunningTotals>,
+    /// Aggregates of every completed daemon window
+    cumulative: Mutex<Aggregates>,
+    daemon: Option<DaemonReports>,
+    metrics: Option<Arc<Metrics>>,
+    leader_schedule: Option<LeaderSchedule>,
+    /// Sources that cannot drop failed transactions while another source does
+    unfiltered_failures: Vec<String>,
+    dump: Mutex<Option<ObservationWriter>>,
+    /// Receive timestamps of the current run are measured from here
+    epoch: RunEpoch,
     start_time: Instant,
+    warmup_duration: Duration,
+    run_duration: Option<Duration>,
+    interrupted: bool,
 }
 
 impl Benchmark {
-    pub fn new(geyser_urls: HashMap<String, String>, geyser_tokens: HashMap<String, String>, shredlink_url: String) -> Self {
+    pub fn new(sources: Vec<Box<dyn StreamSource>>, baseline: String, reconnect_policy: ReconnectPolicy) -> Self {
+        let source_names: Vec<String> = sources.iter().map(|source| source.name().to_string()).collect();
+        let quorum = source_names.len();
+        
         Self {
-            geyser_urls,
-            geyser_tokens,
-            shredlink_url,
+            sources,
+            source_names,
+            baseline,
+            reconnect_policy,
             transactions: Arc::new(DashMap::new()),
+            pair_histograms: Arc::new(DashMap::new()),
+            slot_arrivals: Arc::new(SlotArrivals::default()),
+            slot_updates: Arc::new(SlotArrivals::default()),
+            matched: Arc::new(AtomicUsize::new(0)),
+            source_health: Arc::new(DashMap::new()),
+            gate: Arc::new(MeasurementGate::default()),
+            warmup: None,
+            quorum,
+            tie_tolerance_ns: 0,
+            match_window: DEFAULT_MATCH_WINDOW,
+            totals: Mutex::new(RunningTotals::default()),
+            cumulative: Mutex::new(Aggregates::default()),
+            daemon: None,
+            metrics: None,
+            leader_schedule: None,
+            unfiltered_failures: Vec::new(),
+            dump: Mutex::new(None),
+            epoch: RunEpoch::start(),
             start_time: Instant::now(),
+            warmup_duration: Duration::ZERO,
+            run_duration: None,
+            interrupted: false,
         }
     }
     
+    /// Discard observations until the warm-up is over
+    pub fn with_warmup(mut self, warmup: Warmup) -> Self {
+        self.warmup = Some(warmup);
+        self
+    }
+    
+    /// Rank first arrivals over transactions seen by at least this many sources (all by default)
+    pub fn with_quorum(mut self, quorum: usize) -> Self {
+        self.quorum = quorum;
+        self
+    }
+    
+    /// Count arrival differences within this tolerance as ties instead of wins or losses
+    pub fn with_tie_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tie_tolerance_ns = tolerance.as_nanos() as i64;
+        self
+    }
+    
+    /// Finalize a transaction this long after its first arrival even if some source never delivered it
+    pub fn with_match_window(mut self, window: Duration) -> Self {
+        self.match_window = window;
+        self
+    }
+    
+    /// Run until interrupted instead of for a fixed duration, reporting every `interval`
+    pub fn with_daemon(mut self, daemon: DaemonReports) -> Self {
+        self.daemon = Some(daemon);
+        self
+    }
+    
+    /// Keep these Prometheus metrics up to date while running
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
+    /// Break latency down by the leader of each transaction's slot
+    pub fn with_leader_schedule(mut self, schedule: LeaderSchedule) -> Self {
+        self.leader_schedule = Some(schedule);
+        self
+    }
+    
+    /// Count transactions delivered only by these sources apart, as they may have failed and been dropped elsewhere
+    pub fn with_unfiltered_failures(mut self, sources: Vec<String>) -> Self {
+        self.unfiltered_failures = sources;
+        self
+    }
+    
+    /// Write every transaction to the dump as it is finalized
+    pub fn with_dump(self, writer: ObservationWriter) -> Self {
+        *self.dump.lock().unwrap() = Some(writer);
+        self
+    }
+    
     pub async fn run(&mut self, duration: Duration) -> Result<()> {
-        self.start_time = Instant::now();
+        if self.daemon.as_ref().is_some_and(|daemon| daemon.format == ReportFormat::Table && daemon.output.is_some()) {
+            return Err(table_to_file());
+        }
+        
+        // Anchor every receive timestamp of this run to the same monotonic epoch before any client connects
+        self.epoch = RunEpoch::start();
+        let run_start = self.epoch.instant();
+        self.start_time = run_start;
+        if self.warmup.is_none() {
+            self.gate.open(self.epoch.timestamp_ns());
+        }
         
-        // Setup channels for transaction streaming
-        let mut geyser_channels = HashMap::new();
-        let mut geyser_handlers = Vec::new();
+        // Setup one channel and handler per source
+        let mut source_channels = Vec::new();
+        let mut source_handlers = Vec::new();
         
-        // Create channels for each Geyser source
-        for (geyser_name, _) in &self.geyser_urls {
+        for _ in &self.source_names {
             let (tx, rx) = mpsc::unbounded_channel();
-            geyser_channels.insert(geyser_name.clone(), tx);
+            source_channels.push(tx);
             
-            // Start handler for this Geyser source
-            let handler = self.start_geyser_handler(geyser_name.clone(), rx).await;
-            geyser_handlers.push(handler);
+            let handler = self.start_source_handler(rx).await;
+            source_handlers.push(handler);
         }
         
-        let (shredlink_tx, shredlink_rx) = mpsc::unbounded_channel();
-        
         // Setup progress tracking
         let progress = self.create_progress_bar(duration);
         
-        // Start Shredlink handler
-        let _shredlink_handler = self.start_shredlink_handler(shredlink_rx).await;
-        
         // Start clients in a simple way (avoiding async/Send issues)
-        println!("🔄 Starting clients...");
-        
-        // Start all clients concurrently using join_all
-        let mut client_futures: Vec<Pin<Box<dyn Future<Output = ()>>>> = Vec::new();
-        
-        // Create futures for all Geyser clients
-        for (geyser_name, geyser_url) in self.geyser_urls.clone() {
-            if let Some(tx) = geyser_channels.get(&geyser_name).cloned() {
-                let token = self.geyser_tokens.get(&geyser_name).cloned();
-                let mut client = GeyserStreamClient::new(geyser_url, token);
-                let name = geyser_name.clone();
-                client_futures.push(Box::pin(async move {
-                    if let Err(e) = client.start(tx).await {
-                        eprintln!("❌ {} failed: {}", name, e);
-                    }
-                }) as Pin<Box<dyn Future<Output = ()>>>);
-            }
-        }
+        status!("🔄 Starting clients...");
+        
+        // Sources are moved out while running so the progress loop can still borrow self
+        let mut sources = std::mem::take(&mut self.sources);
         
-        // Add Shredlink client future
-        let mut shredlink_client = ShredlinkClient::new(self.shredlink_url.clone());
-        client_futures.push(Box::pin(async move {
-            if let Err(e) = shredlink_client.start(shredlink_tx).await {
-                eprintln!("❌ Shredlink failed: {}", e);
+        {
+            // Start all clients concurrently using join_all
+            let mut client_futures: Vec<Pin<Box<dyn Future<Output = ()> + '_>>> = Vec::new();
+            
+            for (source, tx) in sources.iter_mut().zip(source_channels) {
+                let policy = &self.reconnect_policy;
+                let health = &*self.source_health;
+                client_futures.push(Box::pin(run_with_reconnect(source.as_mut(), tx, policy, health, self.epoch)));
+            }
+            
+            // Race all clients against the timer and shutdown signals
+            tokio::select! {
+                _ = futures::future::join_all(client_futures) => {},
+                _ = self.measure(duration, &progress) => {},
+                _ = shutdown_signal() => self.interrupted = true,
             }
-        }) as Pin<Box<dyn Future<Output = ()>>>);
+        }
         
-        // Race all clients against the timer
-        tokio::select! {
-            _ = futures::future::join_all(client_futures) => {},
-            _ = self.run_with_progress(duration, &progress) => {},
+        // Measurement starts when the gate opens; an interrupted warm-up measures nothing
+        if self.warmup.is_some() {
+            self.start_time = match self.gate.opened_at.get() {
+                Some(&opened_at) => self.epoch.instant() + Duration::from_nanos(opened_at),
+                None => Instant::now(),
+            };
         }
+        self.warmup_duration = self.start_time.saturating_duration_since(run_start);
+        self.run_duration = Some(self.start_time.elapsed());
+        self.sources = sources;
+        
+        // Clients are stopped, so their senders are gone; let the handlers drain what is queued
+        futures::future::join_all(source_handlers).await;
         
-        progress.finish_with_message("✅ Benchmark completed");
+        // The run is over, so whatever is still pending is final
+        self.evict(u64::MAX);
+        
+        if self.interrupted && self.daemon.is_some() {
+            progress.finish_with_message("🛑 Daemon stopped");
+        } else if self.interrupted {
+            progress.abandon_with_message("⚠️  Benchmark interrupted, reporting partial results");
+        } else {
+            progress.finish_with_message("✅ Benchmark completed");
+        }
         Ok(())
     }
     
     /// Generate final benchmark report
     pub fn generate_report(&self) -> BenchmarkReport {
-        let end_time = Utc::now();
-        let start_time = end_time - chrono::Duration::from_std(self.start_time.elapsed()).unwrap();
+        let elapsed = self.run_duration.unwrap_or_else(|| self.start_time.elapsed());
         
-        let mut geyser_results = HashMap::new();
+        let mut aggregates = self.cumulative.lock().unwrap().clone();
+        aggregates.merge(&self.current_window());
         
-        // Calculate stats for each Geyser source
-        for geyser_name in self.geyser_urls.keys() {
-            let latencies: Vec<i64> = self.transactions
-                .iter()
-                .filter_map(|entry| {
-                    entry.latency_diffs_ms().get(geyser_name).copied()
-                })
-                .collect();
-            
-            let matched_count = latencies.len();
-            let geyser_only_count = self.count_geyser_only(geyser_name);
+        let mut report = self.build_report(&aggregates, self.start_time, elapsed);
+        report.total_transactions += self.transactions.len();
+        report.connection_stats = self.connection_stats();
+        report
+    }
+    
+    /// Report over `aggregates`, which cover `duration` from `start`
+    fn build_report(&self, aggregates: &Aggregates, start: Instant, duration: Duration) -> BenchmarkReport {
+        let start_time = Utc::now() - chrono::Duration::from_std(start.elapsed()).unwrap();
+        let end_time = start_time + chrono::Duration::from_std(duration).unwrap();
+        let totals = &aggregates.totals;
+        
+        let mut source_results = HashMap::new();
+        
+        // Calculate stats for each source against the baseline
+        for source_name in self.source_names.iter().filter(|name| **name != self.baseline) {
+            let latencies = self.pair_histogram(aggregates, &self.baseline, source_name);
+            let matched_count = latencies.len() as usize;
+            let source_only_count = totals.source_only.get(source_name).copied().unwrap_or(0);
             
-            let stats = LatencyStats::calculate(&latencies);
+            let stats = LatencyStats::from_histogram(&latencies);
             
-            geyser_results.insert(geyser_name.clone(), GeyserStats {
+            source_results.insert(source_name.clone(), GeyserStats {
                 matched_transactions: matched_count,
-                geyser_only_count,
-                average_latency_ms: stats.average,
-                median_latency_ms: stats.median,
-                p95_latency_ms: stats.p95,
-                p99_latency_ms: stats.p99,
-                min_latency_ms: stats.min,
-                max_latency_ms: stats.max,
-                shredlink_wins_percentage: stats.shredlink_wins_percentage,
+                source_only_count,
+                average_latency_ms: ns_to_ms(stats.average),
+                median_latency_ms: ns_to_ms(stats.median),
+                p95_latency_ms: ns_to_ms(stats.p95),
+                p99_latency_ms: ns_to_ms(stats.p99),
+                min_latency_ms: ns_to_ms(stats.min as f64),
+                max_latency_ms: ns_to_ms(stats.max as f64),
+                average_latency_us: ns_to_us(stats.average),
+                median_latency_us: ns_to_us(stats.median),
+                p95_latency_us: ns_to_us(stats.p95),
+                p99_latency_us: ns_to_us(stats.p99),
+                min_latency_us: ns_to_us(stats.min as f64),
+                max_latency_us: ns_to_us(stats.max as f64),
+                baseline_wins: stats.wins,
+                ties: stats.ties,
+                baseline_losses: stats.losses,
+                baseline_wins_percentage: stats.baseline_wins_percentage,
+                median_latency_ci_ms: stats::bootstrap_ci(&latencies, histogram::median_of).scaled(1e-6),
+                p95_latency_ci_ms: stats::bootstrap_ci(&latencies, |buckets, len| histogram::percentile_of(buckets, len, 0.95)).scaled(1e-6),
+                baseline_wins_ci_percentage: stats::wilson_interval(stats.wins, matched_count),
+                sign_test_p_value: stats::sign_test(stats.wins, stats.losses),
+                wilcoxon_p_value: stats::wilcoxon_signed_rank(&latencies),
             });
         }
         
         BenchmarkReport {
             start_time,
             end_time,
-            duration_seconds: self.start_time.elapsed().as_secs_f64(),
-            total_transactions: self.transactions.len(),
-            shredlink_only_count: self.count_shredlink_only(),
-            geyser_results,
+            duration_seconds: duration.as_secs_f64(),
+            warmup_seconds: self.warmup_duration.as_secs_f64(),
+            interrupted: self.interrupted,
+            total_transactions: totals.transactions,
+            baseline: self.baseline.clone(),
+            baseline_only_count: totals.baseline_only,
+            possibly_failed_count: totals.possibly_failed,
+            match_window_seconds: self.match_window.as_secs_f64(),
+            missed: self.source_names
+                .iter()
+                .map(|name| (name.clone(), totals.missed.get(name).copied().unwrap_or(0)))
+                .collect(),
+            tie_tolerance_ms: ns_to_ms(self.tie_tolerance_ns as f64),
+            source_results,
+            pairwise: self.pairwise_stats(aggregates),
+            leaderboard: self.leaderboard(totals),
+            slots: totals.slots.analysis(&self.source_names),
+            slot_updates: totals.slot_updates.analysis(&self.source_names),
+            leaders: self.leader_schedule.as_ref().map(|_| totals.leaders.breakdown(&self.source_names)),
+            connection_stats: HashMap::new(),
+        }
+    }
+    
+    /// Copy of what the current window has aggregated so far
+    fn current_window(&self) -> Aggregates {
+        Aggregates {
+            pairs: self.pair_histograms
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.value().clone()))
+                .collect(),
+            totals: self.totals.lock().unwrap().clone(),
+        }
+    }
+    
+    /// Move the current window's aggregates out, starting an empty window
+    fn take_window(&self) -> Aggregates {
+        let keys: Vec<(String, String)> = self.pair_histograms.iter().map(|entry| entry.key().clone()).collect();
+        
+        Aggregates {
+            // Removing key by key lets handlers keep recording; anything after the removal lands in the next window
+            pairs: keys.into_iter().filter_map(|key| self.pair_histograms.remove(&key)).collect(),
+            totals: std::mem::take(&mut *self.totals.lock().unwrap()),
         }
     }
     
-    pub fn print_report(&self, _format: &str) {
+    /// Print the report as a table and/or write it in a machine-readable format
+    pub fn print_report(&self, format: ReportFormat, output: Option<&Path>) -> Result<()> {
+        if format == ReportFormat::Table && output.is_some() {
+            return Err(table_to_file());
+        }
         let report = self.generate_report();
-        self.print_table_report(&report)
+        
+        // Humans still get the table when machine output goes to a file
+        if format == ReportFormat::Table || output.is_some() {
+            self.print_table_report(&report);
+        }
+        
+        if format != ReportFormat::Table {
+            let rendered = report::render(&report, format)?;
+            match output {
+                Some(path) => {
+                    fs::write(path, rendered)?;
+                    status!("📝 Report written to {}", path.display());
+                }
+                None => print!("{}", rendered),
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Close the observation dump, if one was written
+    pub fn finish_dump(&self) -> Result<()> {
+        if let Some(writer) = self.dump.lock().unwrap().take() {
+            let path = writer.path().to_path_buf();
+            let rows = writer.finish()?;
+            status!("📝 {} observations written to {}", rows, path.display());
+        }
+        Ok(())
     }
     
     // --- Private Implementation ---
     
     fn create_progress_bar(&self, duration: Duration) -> ProgressBar {
+        // A daemon has no end to count towards
+        if self.daemon.is_some() {
+            let pb = ProgressBar::new_spinner();
+            pb.set_style(ProgressStyle::default_spinner().template("{spinner:.green} [{elapsed_precise}] {msg}").unwrap());
+            return pb;
+        }
+        
         let pb = ProgressBar::new(duration.as_secs());
         pb.set_style(
             ProgressStyle::default_bar()
@@ -217,66 +647,150 @@ impl Benchmark {
         pb
     }
     
-    async fn start_geyser_handler(&self, geyser_name: String, mut rx: mpsc::UnboundedReceiver<GeyserTransaction>) -> tokio::task::JoinHandle<()> {
+    async fn start_source_handler(&self, mut rx: mpsc::UnboundedReceiver<SourceEvent>) -> tokio::task::JoinHandle<()> {
         let transactions = Arc::clone(&self.transactions);
+        let pair_histograms = Arc::clone(&self.pair_histograms);
+        let slot_arrivals = Arc::clone(&self.slot_arrivals);
+        let slot_updates = Arc::clone(&self.slot_updates);
+        let matched = Arc::clone(&self.matched);
+        let tie_tolerance_ns = self.tie_tolerance_ns;
+        let source_health = Arc::clone(&self.source_health);
+        let gate = Arc::clone(&self.gate);
+        let baseline = self.baseline.clone();
+        let metrics = self.metrics.clone();
         
         tokio::spawn(async move {
-            while let Some(transaction) = rx.recv().await {
-                let timestamp = get_timestamp_ms();
-                let signature = transaction.signature;
+            while let Some(event) = rx.recv().await {
+                // The first message after a reconnect ends the source's downtime
+                let reconnected = source_health
+                    .get_mut(event.source())
+                    .is_some_and(|mut health| health.mark_up(event.received_at()));
+                if let Some(metrics) = metrics.as_ref().filter(|_| reconnected) {
+                    metrics.record_reconnect(event.source());
+                }
+                
+                let observation = match event {
+                    SourceEvent::Transaction(observation) => observation,
+                    SourceEvent::Slot(update) => {
+                        if gate.is_measuring(update.received_at) {
+                            slot_updates.record((update.slot, update.status), &update.source, update.received_at);
+                        }
+                        continue;
+                    }
+                };
+                if let Some(metrics) = &metrics {
+                    metrics.record_received(&observation.source, observation.slot);
+                }
                 
-                transactions
-                    .entry(signature.clone())
-                    .and_modify(|entry| {
-                        if !entry.geyser_timestamps.contains_key(&geyser_name) {
-                            entry.geyser_timestamps.insert(geyser_name.clone(), timestamp);
+                if !gate.admit(&observation) {
+                    continue;
+                }
+                
+                let source_name = observation.source;
+                let timestamp = observation.received_at;
+                if let Some(slot) = observation.slot {
+                    slot_arrivals.record(slot, &source_name, timestamp);
+                }
+                
+                match transactions.entry(observation.signature) {
+                    Entry::Occupied(mut occupied) => {
+                        let entry = occupied.get_mut();
+                        if !entry.has_source(&source_name) {
+                            let was_matched = entry.has_source(&baseline) && entry.timestamps.len() > 1;
+                            
+                            // Every source that already delivered now forms a complete pair with this one
+                            for (other_name, &other_timestamp) in &entry.timestamps {
+                                let diff = timestamp as i64 - other_timestamp as i64;
+                                record_pair(&pair_histograms, other_name, &source_name, diff, tie_tolerance_ns);
+                                record_pair(&pair_histograms, &source_name, other_name, -diff, tie_tolerance_ns);
+                                
+                                if let Some(metrics) = &metrics {
+                                    metrics.record_pair(other_name, &source_name, diff);
+                                    if *other_name == baseline {
+                                        metrics.record_matched(&source_name);
+                                    } else if source_name == baseline {
+                                        metrics.record_matched(other_name);
+                                    }
+                                }
+                            }
+                            entry.timestamps.insert(source_name.clone(), timestamp);
+                            if let Some(slot) = observation.slot {
+                                entry.slots.insert(source_name.clone(), slot);
+                            }
                             
-                            // Only print latency if we have Shredlink data for comparison
-                            if let Some(diff) = entry.latency_diffs_ms().get(&geyser_name) {
-                                println!("⏱️  {}: {}ms", geyser_name, diff);
+                            if !was_matched && entry.has_source(&baseline) {
+                                matched.fetch_add(1, Ordering::Relaxed);
+                                if let Some(metrics) = &metrics {
+                                    metrics.record_matched(&baseline);
+                                }
+                            }
+                            
+                            // Only print latencies that involve the baseline
+                            let diffs = entry.latency_diffs_ns(&baseline);
+                            if source_name == baseline {
+                                for (other_name, diff) in diffs {
+                                    status!("⏱️  {}: {:.3}ms", other_name, ns_to_ms(diff as f64));
+                                }
+                            } else if let Some(diff) = diffs.get(&source_name) {
+                                status!("⏱️  {}: {:.3}ms", source_name, ns_to_ms(*diff as f64));
                             }
                         }
-                    })
-                    .or_insert_with(|| {
+                    }
+                    Entry::Vacant(vacant) => {
+                        // Checked again under the entry lock, as eviction marks a transaction finalized before releasing it
+                        if gate.finalized_signatures.contains_key(vacant.key()) {
+                            continue;
+                        }
                         let mut entry = TransactionTimestamp::new();
-                        entry.geyser_timestamps.insert(geyser_name.clone(), timestamp);
-                        entry
-                    });
+                        entry.timestamps.insert(source_name.clone(), timestamp);
+                        entry.slot = observation.slot;
+                        if let Some(slot) = observation.slot {
+                            entry.slots.insert(source_name.clone(), slot);
+                        }
+                        vacant.insert(entry);
+                    }
+                }
             }
         })
     }
     
-    async fn start_shredlink_handler(&self, mut rx: mpsc::UnboundedReceiver<ShredlinkTransaction>) -> tokio::task::JoinHandle<()> {
-        let transactions = Arc::clone(&self.transactions);
+    /// Warm up if configured, then measure for `duration`
+    async fn measure(&self, duration: Duration, progress: &ProgressBar) {
+        if let Some(warmup) = self.warmup {
+            self.wait_for_warmup(warmup, progress).await;
+        }
+        match &self.daemon {
+            Some(daemon) => self.run_daemon(daemon, progress).await,
+            None => self.run_with_progress(duration, progress).await,
+        }
+    }
+    
+    /// Wait until the minimum warm-up has passed and every source has delivered, or the timeout hits
+    async fn wait_for_warmup(&self, warmup: Warmup, progress: &ProgressBar) {
+        progress.set_message("Warming up...");
+        let start = Instant::now();
         
-        tokio::spawn(async move {
-            while let Some(transaction) = rx.recv().await {
-                let timestamp = get_timestamp_ms();
-                
-                let signature = match transaction.signatures.first() {
-                    Some(sig_bytes) => bs58::encode(sig_bytes).into_string(),
-                    None => continue,
-                };
-                
-                transactions
-                    .entry(signature.clone())
-                    .and_modify(|entry| {
-                        if entry.shredlink_timestamp.is_none() {
-                            entry.shredlink_timestamp = Some(timestamp);
-                            
-                            // Print latencies for all Geyser sources that we have
-                            for (geyser_name, diff) in entry.latency_diffs_ms() {
-                                println!("⏱️  {}: {}ms", geyser_name, diff);
-                            }
-                        }
-                    })
-                    .or_insert_with(|| {
-                        let mut entry = TransactionTimestamp::new();
-                        entry.shredlink_timestamp = Some(timestamp);
-                        entry
-                    });
+        loop {
+            let elapsed = start.elapsed();
+            let silent: Vec<&str> = self.source_names
+                .iter()
+                .filter(|name| !self.gate.ready_sources.contains(*name))
+                .map(|name| name.as_str())
+                .collect();
+            
+            if elapsed >= warmup.duration && silent.is_empty() {
+                break;
             }
-        })
+            if elapsed >= warmup.timeout.max(warmup.duration) {
+                progress.println(format!("⚠️  Warm-up timed out waiting for {}", silent.join(", ")));
+                break;
+            }
+            
+            sleep(Duration::from_millis(50)).await;
+        }
+        
+        self.gate.open(self.epoch.timestamp_ns());
+        progress.println(format!("🔥 Warm-up finished after {:.1}s, measuring", start.elapsed().as_secs_f64()));
     }
     
     async fn run_with_progress(&self, duration: Duration, progress: &ProgressBar) {
@@ -285,42 +799,371 @@ impl Benchmark {
         while start.elapsed() < duration {
             sleep(Duration::from_secs(1)).await;
             progress.inc(1);
+            self.evict_expired();
+            progress.set_message(self.progress_message());
+        }
+    }
+    
+    /// Report every interval until stopped; each window is then folded into the cumulative totals
+    async fn run_daemon(&self, daemon: &DaemonReports, progress: &ProgressBar) {
+        let measuring_since = Instant::now();
+        let mut window_start = measuring_since;
+        let mut window_connections = self.connection_stats();
+        let mut first_window = true;
+        
+        // Fixed-rate ticks keep windows from drifting by the time each tick's work takes
+        let ticks_per_window = daemon.interval.as_secs().max(1);
+        let mut ticker = interval_at(tokio::time::Instant::now() + Duration::from_secs(1), Duration::from_secs(1));
+        let mut ticks = 0;
+        
+        loop {
+            ticker.tick().await;
+            progress.tick();
+            self.evict_expired();
+            progress.set_message(self.progress_message());
+            
+            ticks += 1;
+            if ticks % ticks_per_window != 0 {
+                continue;
+            }
             
-            let stats = self.get_current_stats();
-            progress.set_message(format!(
-                "Total: {} | Matched: {} | Rate: {:.1}/s",
-                stats.total,
-                stats.matched,
-                stats.rate
-            ));
+            let window = self.take_window();
+            let window_end = Instant::now();
+            let connections = self.connection_stats();
+            
+            let mut window_report = self.build_report(&window, window_start, window_end - window_start);
+            window_report.warmup_seconds = 0.0;
+            window_report.connection_stats = connection_stats_since(&window_connections, &connections);
+            
+            let cumulative = {
+                let mut cumulative = self.cumulative.lock().unwrap();
+                cumulative.merge(&window);
+                cumulative.clone()
+            };
+            let mut cumulative_report = self.build_report(&cumulative, measuring_since, window_end - measuring_since);
+            cumulative_report.connection_stats = connections.clone();
+            
+            let emitted = progress.suspend(|| self.emit_window(daemon, &window_report, &cumulative_report, first_window));
+            if let Err(e) = emitted {
+                progress.println(format!("⚠️  Failed to write the window report: {}", e));
+            }
+            
+            window_start = window_end;
+            window_connections = connections;
+            first_window = false;
         }
     }
     
+    /// Print or append one window report together with the cumulative one
+    fn emit_window(&self, daemon: &DaemonReports, window: &BenchmarkReport, cumulative: &BenchmarkReport, first_window: bool) -> Result<()> {
+        if let Some(writer) = self.dump.lock().unwrap().as_mut() {
+            writer.flush()?;
+        }
+        
+        if daemon.format == ReportFormat::Table {
+            status!();
+            status!("🪟 Window {} – {}", window.start_time.to_rfc3339(), window.end_time.to_rfc3339());
+            self.print_table_report(window);
+            self.print_cumulative_summary(cumulative);
+            return Ok(());
+        }
+        
+        match &daemon.output {
+            Some(path) => {
+                let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+                let header = file.metadata()?.len() == 0;
+                file.write_all(report::render_window(window, cumulative, daemon.format, header)?.as_bytes())?;
+            }
+            None => print!("{}", report::render_window(window, cumulative, daemon.format, first_window)?),
+        }
+        Ok(())
+    }
+    
+    /// One line per source with the totals since measurement started
+    fn print_cumulative_summary(&self, report: &BenchmarkReport) {
+        let cyan = Style::new().cyan();
+        
+        status!();
+        status!("{}", cyan.apply_to(format!("📈 CUMULATIVE SINCE {} ({} transactions)", report.start_time.to_rfc3339(), report.total_transactions)));
+        let mut results: Vec<_> = report.source_results.iter().collect();
+        results.sort_by(|a, b| a.0.cmp(b.0));
+        for (source_name, stats) in results {
+            status!(
+                "  {}: {} matched | median {:.3}ms | p95 {:.3}ms | {} wins {:.1}% | missed {}",
+                source_name, stats.matched_transactions, stats.median_latency_ms, stats.p95_latency_ms,
+                report.baseline, stats.baseline_wins_percentage, report.missed.get(source_name).copied().unwrap_or(0)
+            );
+        }
+    }
+    
+    fn progress_message(&self) -> String {
+        let stats = self.get_current_stats();
+        let mut message = format!(
+            "Total: {} | Matched: {} | Rate: {:.1}/s",
+            stats.total,
+            stats.matched,
+            stats.rate
+        );
+        if !stats.medians.is_empty() {
+            let medians: Vec<String> = stats.medians
+                .iter()
+                .map(|(name, median)| format!("{} {:+.3}ms", name, ns_to_ms(*median)))
+                .collect();
+            message.push_str(&format!(" | Median: {}", medians.join(", ")));
+        }
+        message
+    }
+    
+    /// Evict what is past the match window and forget evicted signatures and slots once late arrivals are past it too
+    fn evict_expired(&self) {
+        let window_ns = self.match_window.as_nanos() as u64;
+        let now = self.epoch.timestamp_ns();
+        
+        self.evict(now.saturating_sub(window_ns));
+        self.gate.finalized_signatures.retain(|_, evicted_at| now.saturating_sub(*evicted_at) <= window_ns);
+        self.slot_arrivals.forget_closed(now.saturating_sub(window_ns));
+        self.slot_updates.forget_closed(now.saturating_sub(window_ns));
+    }
+    
     fn get_current_stats(&self) -> CurrentStats {
-        let total = self.transactions.len();
-        let matched = self.transactions.iter().filter(|entry| {
-            entry.has_any_geyser() && entry.shredlink_timestamp.is_some()
-        }).count();
-        let elapsed_secs = self.start_time.elapsed().as_secs_f64();
+        let total = self.totals.lock().unwrap().transactions + self.transactions.len();
+        let matched = self.matched.load(Ordering::Relaxed);
+        let medians = self.source_names
+            .iter()
+            .filter(|name| **name != self.baseline)
+            .filter_map(|name| {
+                let key = (self.baseline.clone(), name.clone());
+                let histogram = self.pair_histograms.get(&key)?;
+                Some((name.clone(), histogram.median()))
+            })
+            .collect();
+        let elapsed_secs = match self.gate.opened_at.get() {
+            Some(&opened_at) => self.epoch.timestamp_ns().saturating_sub(opened_at) as f64 / 1_000_000_000.0,
+            None => 0.0,
+        };
         let rate = if elapsed_secs > 0.0 { total as f64 / elapsed_secs } else { 0.0 };
         
-        CurrentStats { total, matched, rate }
+        CurrentStats { total, matched, rate, medians }
     }
     
-    fn count_geyser_only(&self, geyser_name: &str) -> usize {
-        self.transactions
+    /// Fold and drop transactions every source delivered, and those first seen before `expired_before`
+    fn evict(&self, expired_before: u64) {
+        let finished: Vec<String> = self.transactions
             .iter()
             .filter(|entry| {
-                entry.geyser_timestamps.contains_key(geyser_name) && entry.shredlink_timestamp.is_none()
+                entry.timestamps.len() >= self.source_names.len()
+                    || entry.first_arrival().is_some_and(|first| first < expired_before)
             })
-            .count()
+            .map(|entry| entry.key().clone())
+            .collect();
+        let now = self.epoch.timestamp_ns();
+        let expired_slots = self.slot_arrivals.take_expired(expired_before, now);
+        let expired_updates = self.slot_updates.take_expired(expired_before, now);
+        if finished.is_empty() && expired_slots.is_empty() && expired_updates.is_empty() {
+            return;
+        }
+        
+        let mut totals = self.totals.lock().unwrap();
+        for (_, arrivals) in expired_slots {
+            totals.slots.record_slot(&arrivals, self.tie_tolerance_ns);
+        }
+        for ((_, status), arrivals) in expired_updates {
+            totals.slot_updates.record(status, &arrivals, self.tie_tolerance_ns);
+        }
+        
+        let mut dump = self.dump.lock().unwrap();
+        for signature in finished {
+            // Marked finalized while the entry is still locked, so a late observation cannot start it over
+            let (signature, entry) = match self.transactions.entry(signature) {
+                Entry::Occupied(occupied) => {
+                    self.gate.finalized_signatures.insert(occupied.key().clone(), now);
+                    occupied.remove_entry()
+                }
+                Entry::Vacant(_) => continue,
+            };
+            
+            self.finalize(&signature, &entry, &mut totals);
+            if let Some(writer) = dump.as_mut() {
+                if let Err(e) = writer.write(&signature, &entry) {
+                    eprintln!("⚠️  Stopped writing the observation dump: {}", e);
+                    *dump = None;
+                }
+            }
+        }
     }
     
-    fn count_shredlink_only(&self) -> usize {
-        self.transactions
+    /// Count a finished transaction towards the report
+    fn finalize(&self, signature: &str, entry: &TransactionTimestamp, totals: &mut RunningTotals) {
+        let Some(first) = entry.first_arrival() else {
+            return;
+        };
+        totals.transactions += 1;
+        totals.slots.record_transaction(signature, &entry.slots);
+        
+        // A transaction only sources keeping failed ones delivered may have failed and been dropped by the others
+        let possibly_failed = !self.unfiltered_failures.is_empty()
+            && entry.timestamps.keys().all(|name| self.unfiltered_failures.contains(name));
+        if possibly_failed {
+            totals.possibly_failed += 1;
+        }
+        
+        // Only-seen-by counts ignore transactions an outage explains
+        match entry.timestamps.get(&self.baseline) {
+            _ if possibly_failed => {}
+            Some(&timestamp) if entry.timestamps.len() == 1 => {
+                let explained = self.source_names
+                    .iter()
+                    .any(|name| *name != self.baseline && self.was_down_at(name, timestamp));
+                if !explained {
+                    totals.baseline_only += 1;
+                    if let Some(metrics) = &self.metrics {
+                        metrics.record_only_seen_by(&self.baseline);
+                    }
+                }
+            }
+            Some(_) => {}
+            None => {
+                for (source_name, &timestamp) in &entry.timestamps {
+                    if !self.was_down_at(&self.baseline, timestamp) {
+                        *totals.source_only.entry(source_name.clone()).or_default() += 1;
+                        if let Some(metrics) = &self.metrics {
+                            metrics.record_only_seen_by(source_name);
+                        }
+                    }
+                }
+            }
+        }
+        
+        for name in &self.source_names {
+            if possibly_failed && !self.unfiltered_failures.contains(name) {
+                continue;
+            }
+            if !entry.has_source(name) && !self.was_down_at(name, first) {
+                *totals.missed.entry(name.clone()).or_default() += 1;
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_missed(name);
+                }
+            }
+        }
+        
+        if let Some(schedule) = &self.leader_schedule {
+            if entry.timestamps.len() > 1 {
+                let lags: Vec<(&str, u64)> = entry.timestamps
+                    .iter()
+                    .map(|(source_name, &timestamp)| (source_name.as_str(), timestamp - first))
+                    .collect();
+                totals.leaders.record(schedule, entry.slot, &lags, self.tie_tolerance_ns);
+            }
+        }
+        
+        if entry.timestamps.len() < self.quorum {
+            return;
+        }
+        totals.quorum_transactions += 1;
+        
+        for (source_name, &timestamp) in &entry.timestamps {
+            if timestamp - first <= self.tie_tolerance_ns as u64 {
+                *totals.first_arrivals.entry(source_name.clone()).or_default() += 1;
+            }
+            totals.lags
+                .entry(source_name.clone())
+                .or_insert_with(|| LatencyHistogram::new(0))
+                .record((timestamp - first) as i64);
+        }
+    }
+    
+    /// Compare every source with every other one on the transactions both saw
+    fn pairwise_stats(&self, aggregates: &Aggregates) -> Vec<PairStats> {
+        let mut names: Vec<&String> = self.source_names.iter().collect();
+        names.sort();
+        
+        let mut pairs = Vec::new();
+        for row in &names {
+            for column in names.iter().filter(|column| *column != row) {
+                let diffs = self.pair_histogram(aggregates, row, column);
+                let stats = LatencyStats::from_histogram(&diffs);
+                
+                pairs.push(PairStats {
+                    row: row.to_string(),
+                    column: column.to_string(),
+                    matched_transactions: diffs.len() as usize,
+                    median_diff_ms: ns_to_ms(stats.median),
+                    p95_diff_ms: ns_to_ms(stats.p95),
+                    row_wins: stats.wins,
+                    ties: stats.ties,
+                    row_losses: stats.losses,
+                    row_wins_percentage: stats.baseline_wins_percentage,
+                    sign_test_p_value: stats::sign_test(stats.wins, stats.losses),
+                    wilcoxon_p_value: stats::wilcoxon_signed_rank(&diffs),
+                });
+            }
+        }
+        pairs
+    }
+    
+    /// Rank sources by how often they delivered a quorum transaction first, and by their lag behind the earliest arrival
+    fn leaderboard(&self, totals: &RunningTotals) -> Leaderboard {
+        let transactions = totals.quorum_transactions;
+        
+        let mut sources: Vec<LeaderboardEntry> = self.source_names
             .iter()
-            .filter(|entry| entry.shredlink_timestamp.is_some() && entry.geyser_timestamps.is_empty())
-            .count()
+            .map(|name| {
+                let source_lags = totals.lags.get(name).cloned().unwrap_or_else(|| LatencyHistogram::new(0));
+                let stats = LatencyStats::from_histogram(&source_lags);
+                let first = totals.first_arrivals.get(name).copied().unwrap_or(0);
+                
+                LeaderboardEntry {
+                    source: name.clone(),
+                    seen: source_lags.len() as usize,
+                    first_arrivals: first,
+                    first_arrival_percentage: if transactions > 0 { first as f64 / transactions as f64 * 100.0 } else { 0.0 },
+                    median_lag_ms: ns_to_ms(stats.median),
+                    p95_lag_ms: ns_to_ms(stats.p95),
+                    p99_lag_ms: ns_to_ms(stats.p99),
+                }
+            })
+            .collect();
+        
+        sources.sort_by(|a, b| {
+            b.first_arrivals
+                .cmp(&a.first_arrivals)
+                .then(a.median_lag_ms.total_cmp(&b.median_lag_ms))
+                .then(a.p95_lag_ms.total_cmp(&b.p95_lag_ms))
+        });
+        
+        Leaderboard { quorum: self.quorum, transactions, sources }
+    }
+    
+    /// The (first, second) distribution; empty if the pair never matched
+    fn pair_histogram(&self, aggregates: &Aggregates, first: &str, second: &str) -> LatencyHistogram {
+        aggregates.pairs
+            .get(&(first.to_string(), second.to_string()))
+            .cloned()
+            .unwrap_or_else(|| LatencyHistogram::new(self.tie_tolerance_ns))
+    }
+    
+    fn was_down_at(&self, source_name: &str, timestamp_ns: u64) -> bool {
+        self.source_health
+            .get(source_name)
+            .is_some_and(|health| health.was_down_at(timestamp_ns))
+    }
+    
+    fn connection_stats(&self) -> HashMap<String, ConnectionStats> {
+        let now = self.epoch.timestamp_ns();
+        
+        self.source_names
+            .iter()
+            .map(|name| {
+                let stats = match self.source_health.get(name) {
+                    Some(health) => ConnectionStats {
+                        disconnects: health.disconnects,
+                        downtime_seconds: health.total_downtime(now).as_secs_f64(),
+                    },
+                    None => ConnectionStats { disconnects: 0, downtime_seconds: 0.0 },
+                };
+                (name.clone(), stats)
+            })
+            .collect()
     }
     
 
@@ -331,78 +1174,213 @@ impl Benchmark {
         let red = Style::new().red();
         let yellow = Style::new().yellow();
         
-        println!();
-        println!("{}", green.apply_to("📊 MULTI-GEYSER BENCHMARK RESULTS"));
-        println!("{}", green.apply_to("=".repeat(60)));
+        status!();
+        status!("{}", green.apply_to("📊 MULTI-SOURCE BENCHMARK RESULTS"));
+        status!("{}", green.apply_to("=".repeat(60)));
         
         // Basic stats
-        println!("⏱️  Duration: {:.1}s", report.duration_seconds);
-        println!("📈 Total Transactions: {}", report.total_transactions);
-        println!();
+        status!("⏱️  Duration: {:.1}s", report.duration_seconds);
+        if report.warmup_seconds > 0.0 {
+            status!("🔥 Warm-up: {:.1}s (excluded)", report.warmup_seconds);
+        }
+        if report.interrupted {
+            status!("{}", yellow.apply_to("⚠️  Run was interrupted, results cover a partial run"));
+        }
+        status!("📈 Total Transactions: {}", report.total_transactions);
+        if report.possibly_failed_count > 0 {
+            status!("🧹 Possibly failed (only delivered by sources that keep failed transactions): {}", report.possibly_failed_count);
+        }
+        status!();
         
         // Calculate overall win rate upfront
-        let total_matches: usize = report.geyser_results.values().map(|stats| stats.matched_transactions).sum();
-        let shredlink_wins: usize = report.geyser_results.values()
-            .map(|stats| (stats.shredlink_wins_percentage * stats.matched_transactions as f64 / 100.0) as usize)
-            .sum();
-        let overall_win_rate = if total_matches > 0 { (shredlink_wins as f64 / total_matches as f64) * 100.0 } else { 0.0 };
+        let total_matches: usize = report.source_results.values().map(|stats| stats.matched_transactions).sum();
+        let baseline_wins: usize = report.source_results.values().map(|stats| stats.baseline_wins).sum();
+        let ties: usize = report.source_results.values().map(|stats| stats.ties).sum();
+        let overall_win_rate = if total_matches > 0 { (baseline_wins as f64 / total_matches as f64) * 100.0 } else { 0.0 };
         
         // Highlight the key metric with colors and formatting
-        println!("{}", "═".repeat(60));
-        let win_rate_text = format!("🏆 Shredlink Win Rate: {:.1}% ({} out of {} transactions)", overall_win_rate, shredlink_wins, total_matches);
+        status!("{}", "═".repeat(60));
+        let win_rate_text = format!("🏆 {} Win Rate: {:.1}% ({} out of {} transactions)", report.baseline, overall_win_rate, baseline_wins, total_matches);
         if overall_win_rate > 75.0 {
-            println!("{}", green.apply_to(&format!("🔥 {}", win_rate_text)));
+            status!("{}", green.apply_to(&format!("🔥 {}", win_rate_text)));
         } else if overall_win_rate > 50.0 {
-            println!("{}", cyan.apply_to(&format!("⚡ {}", win_rate_text)));
+            status!("{}", cyan.apply_to(&format!("⚡ {}", win_rate_text)));
         } else {
-            println!("{}", yellow.apply_to(&win_rate_text));
+            status!("{}", yellow.apply_to(&win_rate_text));
         }
-        println!("{}", "═".repeat(60));
+        if ties > 0 {
+            status!("🤝 Ties within ±{:.3}ms: {}", report.tie_tolerance_ms, ties);
+        }
+        status!("{}", "═".repeat(60));
         
-        // Individual Geyser results
-        println!("{}", cyan.apply_to("⚡ INDIVIDUAL GEYSER RESULTS"));
-        println!("{}", cyan.apply_to("-".repeat(40)));
+        // Individual source results
+        status!("{}", cyan.apply_to("⚡ INDIVIDUAL SOURCE RESULTS"));
+        status!("{}", cyan.apply_to("-".repeat(40)));
         
-        for (geyser_name, stats) in &report.geyser_results {
-            println!();
-            println!("{}", yellow.apply_to(format!("🔗 {}", geyser_name)));
+        for (source_name, stats) in &report.source_results {
+            status!();
+            status!("{}", yellow.apply_to(format!("🔗 {}", source_name)));
             
             if stats.matched_transactions > 0 {
-                println!("  🎯 Shredlink wins: {:.1}% of transactions", stats.shredlink_wins_percentage);
-                println!("  📊 Average latency: {:.1}ms", stats.average_latency_ms);
+                status!("  🎯 {} wins: {:.1}% of transactions", report.baseline, stats.baseline_wins_percentage);
+                status!("  ⚖️  Wins / ties / losses: {} / {} / {} (95% CI of win rate: {:.1}%–{:.1}%)", stats.baseline_wins, stats.ties, stats.baseline_losses, stats.baseline_wins_ci_percentage.low, stats.baseline_wins_ci_percentage.high);
+                status!("  📐 Median: {:.3}ms [{:.3}, {:.3}] | P95: {:.3}ms [{:.3}, {:.3}] (95% CI)", stats.median_latency_ms, stats.median_latency_ci_ms.low, stats.median_latency_ci_ms.high, stats.p95_latency_ms, stats.p95_latency_ci_ms.low, stats.p95_latency_ci_ms.high);
+                status!("  🧪 Wilcoxon p={} | sign test p={} {}", format_p_value(stats.wilcoxon_p_value), format_p_value(stats.sign_test_p_value), significance_label(stats.wilcoxon_p_value));
+                status!("  📊 Average latency: {:.3}ms", stats.average_latency_ms);
             } else {
-                println!("  {}", red.apply_to("❌ No matched transactions"));
+                status!("  {}", red.apply_to("❌ No matched transactions"));
+            }
+        }
+        
+        // Who delivers first
+        let leaderboard = &report.leaderboard;
+        status!();
+        status!("{}", cyan.apply_to(format!(
+            "🥇 FIRST-ARRIVAL LEADERBOARD ({} transactions seen by at least {} sources)",
+            leaderboard.transactions, leaderboard.quorum
+        )));
+        status!("{}", cyan.apply_to("-".repeat(40)));
+        if leaderboard.transactions > 0 {
+            for (rank, entry) in leaderboard.sources.iter().enumerate() {
+                status!(
+                    "  {}. {}: first {:.1}% ({}) | lag to first median {:.3}ms, p95 {:.3}ms, p99 {:.3}ms",
+                    rank + 1, entry.source, entry.first_arrival_percentage, entry.first_arrivals,
+                    entry.median_lag_ms, entry.p95_lag_ms, entry.p99_lag_ms
+                );
+            }
+        } else {
+            status!("  {}", red.apply_to("❌ No transactions reached the quorum"));
+        }
+        
+        // Who opens each slot first
+        let slots = &report.slots;
+        if slots.slots > 0 {
+            status!();
+            status!("{}", cyan.apply_to(format!("🧱 PER-SLOT FIRST ARRIVALS ({} slots)", slots.slots)));
+            status!("{}", cyan.apply_to("-".repeat(40)));
+            for source in &slots.sources {
+                status!(
+                    "  {}: first in {:.1}% of slots ({} of {} seen) | lag to first median {:.3}ms, p95 {:.3}ms, p99 {:.3}ms",
+                    source.source, source.first_in_slot_percentage, source.first_in_slot, source.slots_seen,
+                    source.median_lag_ms, source.p95_lag_ms, source.p99_lag_ms
+                );
+            }
+        }
+        if slots.disagreements > 0 {
+            status!("  {}", red.apply_to(format!("⚠️  {} transactions were reported in different slots, e.g.:", slots.disagreements)));
+            for example in slots.disagreement_examples.iter().take(3) {
+                let placements: Vec<String> = example.slots.iter().map(|(source, slot)| format!("{} {}", source, slot)).collect();
+                status!("    {}: {}", example.signature, placements.join(", "));
+            }
+        }
+        
+        // Which source has the freshest chain tip
+        if !report.slot_updates.is_empty() {
+            status!();
+            status!("{}", cyan.apply_to("⛓️  SLOT UPDATES (first announcement of each slot status)"));
+            status!("{}", cyan.apply_to("-".repeat(40)));
+            for status in &report.slot_updates {
+                status!("  {} ({} slots):", status.status.as_str(), status.slots);
+                for source in &status.sources {
+                    status!(
+                        "    {}: first in {:.1}% ({} of {} announced) | lag to first median {:.3}ms, p95 {:.3}ms, p99 {:.3}ms",
+                        source.source, source.first_in_slot_percentage, source.first_in_slot, source.slots_seen,
+                        source.median_lag_ms, source.p95_lag_ms, source.p99_lag_ms
+                    );
+                }
+            }
+        }
+        
+        // Which leaders and regions each source is slow for
+        if let Some(leaders) = &report.leaders {
+            print_leader_groups(&format!("👑 BY LEADER, top {} of {}", TOP_LEADERS.min(leaders.by_leader.len()), leaders.by_leader.len()), &leaders.by_leader[..TOP_LEADERS.min(leaders.by_leader.len())]);
+            if !leaders.by_region.is_empty() {
+                print_leader_groups("🌍 BY LEADER REGION", &leaders.by_region);
+            }
+            if leaders.unknown_leader_transactions > 0 {
+                status!("  {}", yellow.apply_to(format!("⚠️  {} transactions had no leader in the schedule", leaders.unknown_leader_transactions)));
+            }
+        }
+        
+        // Every source against every other one
+        if report.source_results.len() > 1 {
+            status!();
+            status!("{}", cyan.apply_to("🔀 PAIRWISE MATRIX (row vs column: median / p95 difference, row win rate)"));
+            status!("{}", cyan.apply_to("-".repeat(40)));
+            for pair in &report.pairwise {
+                status!(
+                    "  {} vs {}: {:+.3} / {:+.3} ms, {:.1}% wins, {} matched, Wilcoxon p={} {}",
+                    pair.row, pair.column, pair.median_diff_ms, pair.p95_diff_ms, pair.row_wins_percentage, pair.matched_transactions,
+                    format_p_value(pair.wilcoxon_p_value), significance_label(pair.wilcoxon_p_value)
+                );
+            }
+        }
+        
+        // Connection stability, only shown when something went wrong
+        let mut unstable: Vec<_> = report.connection_stats.iter().filter(|(_, stats)| stats.disconnects > 0).collect();
+        unstable.sort_by(|a, b| a.0.cmp(b.0));
+        if !unstable.is_empty() {
+            status!();
+            status!("{}", cyan.apply_to("🔌 CONNECTION STABILITY"));
+            status!("{}", cyan.apply_to("-".repeat(40)));
+            for (source_name, stats) in unstable {
+                status!("  {} {}: {} disconnects, {:.1}s downtime", red.apply_to("⚠️"), source_name, stats.disconnects, stats.downtime_seconds);
             }
         }
         
-        // Shredlink Performance Summary
-        println!();
-        println!("{}", cyan.apply_to("🏁 SHREDLINK PERFORMANCE SUMMARY"));
-        println!("{}", cyan.apply_to("-".repeat(40)));
+        // Transactions a source never delivered while another one did
+        let mut missed: Vec<_> = report.missed.iter().filter(|(_, count)| **count > 0).collect();
+        missed.sort_by(|a, b| a.0.cmp(b.0));
+        if !missed.is_empty() {
+            status!();
+            status!("{}", cyan.apply_to(format!("🕳️  MISSED WITHIN THE {:.0}s MATCH WINDOW", report.match_window_seconds)));
+            status!("{}", cyan.apply_to("-".repeat(40)));
+            for (source_name, count) in missed {
+                status!("  {}: {} transactions delivered by another source", source_name, count);
+            }
+        }
         
-        let total_matches: usize = report.geyser_results.values().map(|stats| stats.matched_transactions).sum();
-        let shredlink_wins: usize = report.geyser_results.values()
-            .map(|stats| (stats.shredlink_wins_percentage * stats.matched_transactions as f64 / 100.0) as usize)
-            .sum();
+        // Baseline Performance Summary
+        status!();
+        status!("{}", cyan.apply_to(format!("🏁 {} PERFORMANCE SUMMARY", report.baseline.to_uppercase())));
+        status!("{}", cyan.apply_to("-".repeat(40)));
         
         if total_matches > 0 {
-            let overall_win_rate = (shredlink_wins as f64 / total_matches as f64) * 100.0;
-            println!("🎯 Total Matched Transactions: {}", total_matches);
-            println!("🏆 Shredlink Wins: {} out of {} ({:.1}%)", shredlink_wins, total_matches, overall_win_rate);
-            
-            
+            let baseline_losses: usize = report.source_results.values().map(|stats| stats.baseline_losses).sum();
+            status!("🎯 Total Matched Transactions: {}", total_matches);
+            status!("🏆 {} Wins: {} out of {} ({:.1}%)", report.baseline, baseline_wins, total_matches, overall_win_rate);
+            status!("🤝 Ties: {} | Losses: {}", ties, baseline_losses);
         } else {
-            println!("{}", red.apply_to("❌ No matched transactions for comparison"));
+            status!("{}", red.apply_to("❌ No matched transactions for comparison"));
         }
     }
 }
 
 // --- Helper structs and functions ---
 
+/// One line per group with every source's first-arrival share and lag behind the earliest arrival
+fn print_leader_groups(title: &str, groups: &[crate::leaders::GroupStats]) {
+    let cyan = Style::new().cyan();
+    
+    status!();
+    status!("{}", cyan.apply_to(format!("{} (first % / median lag / p95 lag)", title)));
+    status!("{}", cyan.apply_to("-".repeat(40)));
+    for group in groups {
+        let sources: Vec<String> = group.sources
+            .iter()
+            .filter(|source| source.seen > 0)
+            .map(|source| format!("{} {:.0}% / {:.3}ms / {:.3}ms", source.source, source.first_arrival_percentage, source.median_lag_ms, source.p95_lag_ms))
+            .collect();
+        status!("  {} ({} txs): {}", group.group, group.transactions, sources.join(" | "));
+    }
+}
+
 struct CurrentStats {
     total: usize,
     matched: usize,
     rate: f64,
+    /// Live median difference of each source against the baseline (ns)
+    medians: Vec<(String, f64)>,
 }
 
 struct LatencyStats {
@@ -412,55 +1390,128 @@ struct LatencyStats {
     p99: f64,
     min: i64,
     max: i64,
-    shredlink_wins_percentage: f64,
+    wins: usize,
+    ties: usize,
+    losses: usize,
+    baseline_wins_percentage: f64,
 }
 
 impl LatencyStats {
-    fn calculate(latencies: &[i64]) -> Self {
-        if latencies.is_empty() {
-            return Self {
-                average: 0.0,
-                median: 0.0,
-                p95: 0.0,
-                p99: 0.0,
-                min: 0,
-                max: 0,
-                shredlink_wins_percentage: 0.0,
+    fn from_histogram(histogram: &LatencyHistogram) -> Self {
+        let len = histogram.len();
+        let wins = histogram.wins() as usize;
+        
+        Self {
+            average: histogram.mean(),
+            median: histogram.median(),
+            p95: histogram.percentile(0.95),
+            p99: histogram.percentile(0.99),
+            min: histogram.min(),
+            max: histogram.max(),
+            wins,
+            ties: histogram.ties() as usize,
+            losses: histogram.losses() as usize,
+            baseline_wins_percentage: if len > 0 { wins as f64 / len as f64 * 100.0 } else { 0.0 },
+        }
+    }
+}
+
+/// Disconnects and downtime accumulated between two snapshots
+fn connection_stats_since(before: &HashMap<String, ConnectionStats>, after: &HashMap<String, ConnectionStats>) -> HashMap<String, ConnectionStats> {
+    after
+        .iter()
+        .map(|(name, stats)| {
+            let (disconnects, downtime_seconds) = before
+                .get(name)
+                .map_or((0, 0.0), |before| (before.disconnects, before.downtime_seconds));
+            let delta = ConnectionStats {
+                disconnects: stats.disconnects.saturating_sub(disconnects),
+                downtime_seconds: (stats.downtime_seconds - downtime_seconds).max(0.0),
             };
+            (name.clone(), delta)
+        })
+        .collect()
+}
+
+fn merge_counts<K: Clone + Eq + std::hash::Hash>(into: &mut HashMap<K, usize>, from: &HashMap<K, usize>) {
+    for (key, count) in from {
+        *into.entry(key.clone()).or_default() += count;
+    }
+}
+
+fn merge_histograms<K: Clone + Eq + std::hash::Hash>(into: &mut HashMap<K, LatencyHistogram>, from: &HashMap<K, LatencyHistogram>) {
+    for (key, histogram) in from {
+        match into.get_mut(key) {
+            Some(existing) => existing.merge(histogram),
+            None => {
+                into.insert(key.clone(), histogram.clone());
+            }
         }
+    }
+}
+
+/// Record `diff` (second's arrival minus first's) for the ordered pair
+fn record_pair(
+    pair_histograms: &DashMap<(String, String), LatencyHistogram>,
+    first: &str,
+    second: &str,
+    diff: i64,
+    tie_tolerance_ns: i64,
+) {
+    pair_histograms
+        .entry((first.to_string(), second.to_string()))
+        .or_insert_with(|| LatencyHistogram::new(tie_tolerance_ns))
+        .record(diff);
+}
+
+/// Default time other sources get to deliver a transaction after its first arrival
+pub const DEFAULT_MATCH_WINDOW: Duration = Duration::from_secs(30);
+
+/// Resolves on Ctrl-C (SIGINT) or SIGTERM
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
         
-        let mut sorted = latencies.to_vec();
-        sorted.sort_unstable();
-        
-        let len = sorted.len();
-        let average = sorted.iter().sum::<i64>() as f64 / len as f64;
-        let median = if len % 2 == 0 {
-            (sorted[len / 2 - 1] + sorted[len / 2]) as f64 / 2.0
-        } else {
-            sorted[len / 2] as f64
-        };
-        
-        let p95_idx = ((len as f64 * 0.95) as usize).min(len - 1);
-        let p99_idx = ((len as f64 * 0.99) as usize).min(len - 1);
-        
-        let shredlink_wins = sorted.iter().filter(|&&x| x > 0).count();
-        let shredlink_wins_percentage = (shredlink_wins as f64 / len as f64) * 100.0;
-        
-        Self {
-            average,
-            median,
-            p95: sorted[p95_idx] as f64,
-            p99: sorted[p99_idx] as f64,
-            min: sorted[0],
-            max: sorted[len - 1],
-            shredlink_wins_percentage,
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {},
+                    _ = sigterm.recv() => {},
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
         }
     }
+    
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
 }
 
-fn get_timestamp_ms() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_millis() as u64
-}
\ No newline at end of file
+/// p-values below this are reported as significant
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+fn significance_label(p_value: f64) -> &'static str {
+    if p_value < SIGNIFICANCE_LEVEL { "✅ significant" } else { "❔ not significant" }
+}
+
+pub(crate) fn format_p_value(p_value: f64) -> String {
+    if p_value < 1e-4 { "<0.0001".to_string() } else { format!("{:.4}", p_value) }
+}
+
+fn ns_to_ms(ns: f64) -> f64 {
+    ns / 1_000_000.0
+}
+
+fn ns_to_us(ns: f64) -> f64 {
+    ns / 1_000.0
+}
+
+/// The table is printed as the run goes, so only the machine formats can go to a file
+fn table_to_file() -> anyhow::Error {
+    anyhow::anyhow!("A table report cannot be written to a file; use the json, csv or markdown format")
+}
@@ -0,0 +1,23 @@
+use anyhow::Result;
+use clap::Parser;
+use std::path::PathBuf;
+
+use shredlink_benchmarking::mock::MockScenario;
+
+#[derive(Parser)]
+#[command(name = "shredlink-mock")]
+#[command(about = "Local Yellowstone and Shredlink gRPC servers replaying a scripted transaction stream")]
+struct Cli {
+    /// Scenario file (TOML or YAML) with the transaction script and feeds to serve
+    scenario: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    
+    let scenario = MockScenario::load(&cli.scenario)?;
+    println!("🎭 Replaying {} transactions to {} feeds", scenario.transactions.len(), scenario.feeds.len());
+    
+    scenario.serve().await
+}
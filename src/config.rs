@@ -0,0 +1,270 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::geyser_client::GeyserStreamClient;
+use crate::recording::Recorder;
+use crate::report::{DumpFormat, ReportFormat};
+use crate::shredlink_client::ShredlinkClient;
+use crate::source::StreamSource;
+
+pub const SHREDLINK_SOURCE_NAME: &str = "Shredlink";
+
+/// PumpFun program, the default subscription when no filter is configured
+pub const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+
+/// Vote program, used to apply the `vote` filter on feeds that cannot filter it server-side
+pub const VOTE_PROGRAM_ID: &str = "Vote111111111111111111111111111111111111111";
+
+/// Benchmark configuration file (TOML or YAML)
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub run: RunConfig,
+    #[serde(default)]
+    pub sources: Vec<SourceConfig>,
+}
+
+/// Run parameters; command-line flags take precedence over these
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RunConfig {
+    /// Benchmark duration in seconds
+    pub duration: Option<u64>,
+    /// Run until stopped, reporting every `report_interval` seconds
+    pub daemon: Option<bool>,
+    pub report_interval: Option<u64>,
+    /// Warm-up in seconds before measurement starts
+    pub warmup: Option<u64>,
+    /// Longest wait in seconds for every source to deliver its first message
+    pub warmup_timeout: Option<u64>,
+    pub baseline: Option<String>,
+    /// Minimum number of sources a transaction needs to enter the first-arrival leaderboard
+    pub quorum: Option<usize>,
+    /// Arrival differences up to this many milliseconds count as ties
+    pub tie_tolerance_ms: Option<f64>,
+    /// Seconds other sources get to deliver a transaction before it is finalized
+    pub match_window: Option<u64>,
+    /// `getLeaderSchedule` result to break latency down by slot leader
+    pub leader_schedule: Option<PathBuf>,
+    /// First slot of the schedule's epoch
+    pub epoch_first_slot: Option<u64>,
+    /// Validator identity -> region map
+    pub validator_regions: Option<PathBuf>,
+    pub format: Option<ReportFormat>,
+    pub output: Option<PathBuf>,
+    pub dump: Option<PathBuf>,
+    pub dump_format: Option<DumpFormat>,
+    /// Subscribe this Geyser source at processed and confirmed and report the delay between them
+    pub commitment_delay: Option<String>,
+    /// Serve Prometheus metrics on this address
+    pub metrics_listen: Option<String>,
+    /// Record every received message to this file
+    pub record: Option<PathBuf>,
+    /// Transaction filter for sources that do not define their own
+    pub filter: Option<TransactionFilter>,
+    #[serde(default)]
+    pub reconnect: ReconnectConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReconnectConfig {
+    pub max_retries: Option<u32>,
+    pub backoff_ms: Option<u64>,
+    pub max_backoff_ms: Option<u64>,
+    pub jitter: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    Geyser,
+    Shredlink,
+}
+
+/// One named stream source
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceConfig {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: SourceKind,
+    pub url: String,
+    /// Sent as the `x-token` metadata header
+    pub token: Option<String>,
+    /// Extra metadata headers sent with every request
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub tls: TlsConfig,
+    /// Transaction filter; falls back to `run.filter`, then to PumpFun
+    pub filter: Option<TransactionFilter>,
+    #[serde(default)]
+    pub commitment: Commitment,
+    /// Subscribe to slot status updates (Geyser only); on unless `false`
+    pub slot_updates: Option<bool>,
+}
+
+/// TLS options; TLS is used for `https://` URLs or when any option is set
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// PEM file with extra CA certificate(s) to trust on top of the native roots (Shredlink also trusts the webpki roots)
+    pub ca_cert: Option<PathBuf>,
+    /// PEM client certificate and key for mutual TLS
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+    /// Override the domain name checked against the server certificate
+    pub domain_name: Option<String>,
+}
+
+impl TlsConfig {
+    pub fn is_configured(&self) -> bool {
+        self.ca_cert.is_some() || self.client_cert.is_some() || self.client_key.is_some() || self.domain_name.is_some()
+    }
+}
+
+/// Transaction filter applied the same way to every source type
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionFilter {
+    #[serde(default)]
+    pub account_include: Vec<String>,
+    #[serde(default)]
+    pub account_exclude: Vec<String>,
+    #[serde(default)]
+    pub account_required: Vec<String>,
+    /// `Some(false)` drops vote transactions, `Some(true)` keeps only them, `None` keeps both
+    pub vote: Option<bool>,
+    /// Same as `vote` for failed transactions; only Geyser sources can apply it
+    pub failed: Option<bool>,
+}
+
+impl TransactionFilter {
+    /// Non-vote, successful PumpFun transactions
+    pub fn pumpfun() -> Self {
+        Self {
+            account_required: vec![PUMPFUN_PROGRAM_ID.to_string()],
+            vote: Some(false),
+            failed: Some(false),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Commitment {
+    #[default]
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub const ALL: [Commitment; 3] = [Commitment::Processed, Commitment::Confirmed, Commitment::Finalized];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+}
+
+impl SourceConfig {
+    pub fn new(name: String, kind: SourceKind, url: String) -> Self {
+        Self {
+            name,
+            kind,
+            url,
+            token: None,
+            headers: BTreeMap::new(),
+            tls: TlsConfig::default(),
+            filter: None,
+            commitment: Commitment::default(),
+            slot_updates: None,
+        }
+    }
+
+    pub fn uses_tls(&self) -> bool {
+        self.url.starts_with("https://") || self.tls.is_configured()
+    }
+
+    pub fn filter(&self) -> TransactionFilter {
+        self.filter.clone().unwrap_or_else(TransactionFilter::pumpfun)
+    }
+
+    /// Create the client for this source, recording every received message if a recorder is given
+    pub fn build(self, recorder: Option<&Recorder>) -> Result<Box<dyn StreamSource>> {
+        let recorder = recorder.map(|recorder| recorder.source(&self.name, self.kind)).transpose()?;
+        
+        match self.kind {
+            SourceKind::Geyser => Ok(Box::new(GeyserStreamClient::new(self, recorder))),
+            SourceKind::Shredlink => Ok(Box::new(ShredlinkClient::new(self, recorder))),
+        }
+    }
+}
+
+impl Config {
+    /// Load a config file, picking the parser from the file extension
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        
+        let config: Config = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&contents)
+                .with_context(|| format!("Invalid YAML config {}", path.display()))?,
+            _ => toml::from_str(&contents)
+                .with_context(|| format!("Invalid TOML config {}", path.display()))?,
+        };
+        
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Build the source list from `GEYSER_<NAME>_URL`/`GEYSER_<NAME>_TOKEN` and `SHREDLINK_HOST_URL`/`SHREDLINK_TOKEN`
+    pub fn from_env() -> Self {
+        let mut sources = Vec::new();
+        
+        for (key, value) in env::vars() {
+            if key.starts_with("GEYSER_") && key.ends_with("_URL") && key != "GEYSER_HOST_URL" {
+                if let Some(name_part) = key.strip_prefix("GEYSER_").and_then(|s| s.strip_suffix("_URL")) {
+                    let geyser_name = format!("Geyser-{}", name_part.to_lowercase());
+                    let mut source = SourceConfig::new(geyser_name, SourceKind::Geyser, value);
+                    
+                    // Check for corresponding token
+                    let token_key = format!("GEYSER_{}_TOKEN", name_part);
+                    source.token = env::var(&token_key).ok();
+                    
+                    sources.push(source);
+                }
+            }
+        }
+        sources.sort_by(|a, b| a.name.cmp(&b.name));
+        
+        if let Ok(shredlink_host) = env::var("SHREDLINK_HOST_URL") {
+            let mut source = SourceConfig::new(SHREDLINK_SOURCE_NAME.to_string(), SourceKind::Shredlink, shredlink_host);
+            source.token = env::var("SHREDLINK_TOKEN").ok();
+            sources.push(source);
+        }
+        
+        Self { run: RunConfig::default(), sources }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for source in &self.sources {
+            if !names.insert(source.name.as_str()) {
+                return Err(anyhow::anyhow!("Duplicate source name '{}' in config", source.name));
+            }
+        }
+        Ok(())
+    }
+}
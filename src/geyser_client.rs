@@ -1,112 +1,222 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use futures::future::LocalBoxFuture;
 use futures::StreamExt;
 use std::collections::HashMap;
-use tokio::sync::mpsc::UnboundedSender;
-use yellowstone_grpc_client::{GeyserGrpcClient, Interceptor, ClientTlsConfig};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_stream::wrappers::UnboundedReceiverStream;
 use yellowstone_grpc_proto::prelude::{
-    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
-    SubscribeRequestFilterTransactions,
+    subscribe_update::UpdateOneof, CommitmentLevel, SlotStatus, SubscribeRequest,
+    SubscribeRequestFilterSlots, SubscribeRequestFilterTransactions, SubscribeUpdate,
+};
+use yellowstone_grpc_proto::prost::Message;
+use yellowstone_grpc_proto::tonic::{
+    client::Grpc,
+    codegen::http::uri::PathAndQuery,
+    metadata::{AsciiMetadataKey, AsciiMetadataValue},
+    service::interceptor::InterceptedService,
+    transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity},
+    Request,
 };
 
-#[derive(Debug, Clone)]
-pub struct GeyserTransaction {
-    pub signature: String,
-    pub slot: u64,
-}
+use crate::config::{Commitment, SourceConfig};
+use crate::grpc::{self, RawCodec, TlsFiles};
+use crate::recording::SourceRecorder;
+use crate::source::{Observation, RunEpoch, SlotUpdate, SourceEvent, StreamSource};
+
+const SUBSCRIBE_PATH: &str = "/geyser.Geyser/Subscribe";
+
+type MetadataInterceptor = grpc::MetadataInterceptor<AsciiMetadataKey, AsciiMetadataValue>;
 
 pub struct GeyserStreamClient {
-    endpoint: String,
-    token: Option<String>,
+    config: SourceConfig,
+    recorder: Option<SourceRecorder>,
 }
 
 impl GeyserStreamClient {
-    pub fn new(endpoint: String, token: Option<String>) -> Self {
-        Self { endpoint, token }
+    pub fn new(config: SourceConfig, recorder: Option<SourceRecorder>) -> Self {
+        Self { config, recorder }
     }
 
-    async fn create_client(&self) -> Result<GeyserGrpcClient<impl Interceptor>> {
-        let mut builder = GeyserGrpcClient::build_from_shared(self.endpoint.clone())?;
+    fn tls_config(&self) -> Result<ClientTlsConfig> {
+        let files = TlsFiles::load(&self.config.tls)?;
         
-        // Configure TLS for HTTPS endpoints
-        if self.endpoint.starts_with("https://") {
-            builder = builder.tls_config(ClientTlsConfig::new().with_native_roots())?;
+        // A custom CA is trusted on top of the native roots, as the Shredlink client does
+        let mut tls_config = ClientTlsConfig::new().with_native_roots();
+        if let Some(pem) = files.ca_cert {
+            tls_config = tls_config.ca_certificate(Certificate::from_pem(pem));
+        }
+        if let Some((cert, key)) = files.identity {
+            tls_config = tls_config.identity(Identity::from_pem(cert, key));
+        }
+        if let Some(domain_name) = files.domain_name {
+            tls_config = tls_config.domain_name(domain_name);
         }
         
-        // Configure message size limit
-        builder = builder.max_decoding_message_size(1024 * 1024 * 1024);
+        Ok(tls_config)
+    }
+
+    async fn create_client(&self) -> Result<Grpc<InterceptedService<Channel, MetadataInterceptor>>> {
+        let mut endpoint = Endpoint::from_shared(self.config.url.clone())?;
         
-        // Add token authentication if provided
-        if let Some(token) = &self.token {
-            builder = builder.x_token(Some(token.clone()))?;
+        // Configure TLS for HTTPS endpoints or explicit TLS options
+        if self.config.uses_tls() {
+            endpoint = endpoint.tls_config(self.tls_config()?)?;
         }
-
-        let client = builder.connect().await
+        
+        let channel = endpoint.connect().await
             .map_err(|e| anyhow::anyhow!("gRPC connection failed: {}", e))?;
         
+        // Add token and header authentication if provided
+        let interceptor = MetadataInterceptor::new(&self.config)?;
+        
+        // Configure message size limit
+        let client = Grpc::new(InterceptedService::new(channel, interceptor))
+            .max_decoding_message_size(1024 * 1024 * 1024);
+        
         Ok(client)
     }
 
-    pub async fn start(&mut self, tx: UnboundedSender<GeyserTransaction>) -> Result<()> {
-        let mut client = self.create_client().await?;
+    fn create_request(&self) -> SubscribeRequest {
+        let filter = self.config.filter();
         
-        // Create subscription request for all transactions
+        // Create subscription request for matching transactions
         let mut transactions = HashMap::new();
         transactions.insert(
             "transactions".to_string(),
             SubscribeRequestFilterTransactions {
-                vote: Some(false),
-                failed: Some(false),
+                vote: filter.vote,
+                failed: filter.failed,
                 signature: None,
-                account_include: vec![],
-                account_exclude: vec![],
-                account_required: vec!["6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P".to_string()],
+                account_include: filter.account_include,
+                account_exclude: filter.account_exclude,
+                account_required: filter.account_required,
             }
         );
-
-        let request = SubscribeRequest {
-            slots: HashMap::new(),
+        
+        // Every status of every slot, independent of the transaction commitment
+        let mut slots = HashMap::new();
+        if self.config.slot_updates != Some(false) {
+            slots.insert(
+                "slots".to_string(),
+                SubscribeRequestFilterSlots {
+                    filter_by_commitment: Some(false),
+                    interslot_updates: Some(false),
+                }
+            );
+        }
+        
+        let commitment = match self.config.commitment {
+            Commitment::Processed => CommitmentLevel::Processed,
+            Commitment::Confirmed => CommitmentLevel::Confirmed,
+            Commitment::Finalized => CommitmentLevel::Finalized,
+        };
+        
+        SubscribeRequest {
+            slots,
             accounts: HashMap::new(),
             transactions,
             transactions_status: HashMap::new(),
             entry: HashMap::new(),
             blocks: HashMap::new(),
             blocks_meta: HashMap::new(),
-            commitment: Some(CommitmentLevel::Processed as i32),
+            commitment: Some(commitment as i32),
             accounts_data_slice: vec![],
             ping: None,
             from_slot: None,
-        };
-
-        let (mut _subscribe_tx, mut stream) = client.subscribe_with_request(Some(request)).await
-            .map_err(|e| anyhow::anyhow!("Failed to start subscription: {}", e))?;
+        }
+    }
 
+    async fn subscribe(&mut self, tx: UnboundedSender<SourceEvent>, epoch: RunEpoch) -> Result<()> {
+        let mut client = self.create_client().await?;
+        client.ready().await
+            .map_err(|e| anyhow::anyhow!("Geyser service not ready: {}", e))?;
+        
+        // The request sender must stay alive for the subscription to remain open
+        let (subscribe_tx, subscribe_rx) = mpsc::unbounded_channel();
+        let _ = subscribe_tx.send(Bytes::from(self.create_request().encode_to_vec()));
+        
+        // Messages arrive undecoded, so they can be recorded exactly as received
+        let request = Request::new(UnboundedReceiverStream::new(subscribe_rx));
+        let mut stream = client.streaming(request, PathAndQuery::from_static(SUBSCRIBE_PATH), RawCodec).await
+            .map_err(|e| anyhow::anyhow!("Failed to start subscription: {}", e))?
+            .into_inner();
+        
         // Process the stream
         while let Some(message) = stream.next().await {
             match message {
-                Ok(msg) => {
-                    if let Some(UpdateOneof::Transaction(transaction_update)) = msg.update_oneof {
-                        if let Some(transaction) = transaction_update.transaction {
-                            let signature = bs58::encode(&transaction.signature).into_string();
-                            
-                            let geyser_transaction = GeyserTransaction {
-                                signature,
-                                slot: transaction_update.slot,
-                            };
-
-                            if let Err(e) = tx.send(geyser_transaction) {
-                                eprintln!("❌ Failed to send Geyser transaction: {}", e);
-                                break;
-                            }
+                Ok(payload) => {
+                    let received_at = epoch.timestamp_ns();
+                    
+                    if let Some(recorder) = &self.recorder {
+                        if let Err(e) = recorder.record(received_at, payload.clone()) {
+                            eprintln!("❌ Failed to record {} message: {}", self.config.name, e);
+                        }
+                    }
+                    
+                    let event = decode_event(&self.config.name, &payload, received_at)
+                        .context("Invalid Geyser message")?;
+                    if let Some(event) = event {
+                        if let Err(e) = tx.send(event) {
+                            eprintln!("❌ Failed to send Geyser transaction: {}", e);
+                            break;
                         }
                     }
                 }
                 Err(e) => {
-                    eprintln!("❌ Geyser stream error: {}", e);
-                    break;
+                    return Err(anyhow::anyhow!("Geyser stream error: {}", e));
                 }
             }
         }
-
+        
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Decode a recorded `SubscribeUpdate` into an event
+pub fn decode_event(source: &str, payload: &[u8], received_at: u64) -> Result<Option<SourceEvent>> {
+    let update = SubscribeUpdate::decode(payload)?;
+    Ok(event_from_update(source, update, received_at))
+}
+
+fn event_from_update(source: &str, update: SubscribeUpdate, received_at: u64) -> Option<SourceEvent> {
+    match update.update_oneof? {
+        UpdateOneof::Transaction(transaction_update) => {
+            let transaction = transaction_update.transaction?;
+            
+            Some(SourceEvent::Transaction(Observation {
+                signature: bs58::encode(&transaction.signature).into_string(),
+                slot: Some(transaction_update.slot),
+                source: source.to_string(),
+                received_at,
+            }))
+        }
+        UpdateOneof::Slot(slot_update) => {
+            // Intermediate statuses (first shred, bank created, ...) are not compared
+            let status = match SlotStatus::try_from(slot_update.status).ok()? {
+                SlotStatus::SlotProcessed => Commitment::Processed,
+                SlotStatus::SlotConfirmed => Commitment::Confirmed,
+                SlotStatus::SlotFinalized => Commitment::Finalized,
+                _ => return None,
+            };
+            
+            Some(SourceEvent::Slot(SlotUpdate {
+                slot: slot_update.slot,
+                status,
+                source: source.to_string(),
+                received_at,
+            }))
+        }
+        _ => None,
+    }
+}
+
+impl StreamSource for GeyserStreamClient {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn start(&mut self, tx: UnboundedSender<SourceEvent>, epoch: RunEpoch) -> LocalBoxFuture<'_, Result<()>> {
+        Box::pin(self.subscribe(tx, epoch))
+    }
+}
@@ -0,0 +1,166 @@
+//! gRPC plumbing shared by the source clients.
+//!
+//! The Geyser and Shredlink protos are generated against different tonic versions,
+//! so the passthrough codec and the metadata interceptor implement the traits of both.
+
+use anyhow::{Context, Result};
+use bytes::{Buf, BufMut, Bytes};
+use std::error::Error as StdError;
+use std::fs;
+use std::str::FromStr;
+
+use crate::config::{SourceConfig, TlsConfig};
+
+/// Adds the configured token and headers to every request
+#[derive(Clone)]
+pub struct MetadataInterceptor<K, V> {
+    headers: Vec<(K, V)>,
+}
+
+impl<K, V> MetadataInterceptor<K, V>
+where
+    K: FromStr,
+    K::Err: StdError + Send + Sync + 'static,
+    V: FromStr,
+    V::Err: StdError + Send + Sync + 'static,
+{
+    pub fn new(config: &SourceConfig) -> Result<Self> {
+        let mut headers = Vec::new();
+        
+        if let Some(token) = &config.token {
+            headers.push(("x-token".parse()?, token.parse().context("Invalid token")?));
+        }
+        
+        for (key, value) in &config.headers {
+            let name = key.parse()
+                .with_context(|| format!("Invalid header name '{}'", key))?;
+            let value = value.parse()
+                .with_context(|| format!("Invalid value for header '{}'", key))?;
+            headers.push((name, value));
+        }
+        
+        Ok(Self { headers })
+    }
+}
+
+impl tonic::service::Interceptor for MetadataInterceptor<tonic::metadata::AsciiMetadataKey, tonic::metadata::AsciiMetadataValue> {
+    fn call(&mut self, mut request: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+        for (key, value) in &self.headers {
+            request.metadata_mut().insert(key.clone(), value.clone());
+        }
+        Ok(request)
+    }
+}
+
+impl yellowstone_grpc_proto::tonic::service::Interceptor
+    for MetadataInterceptor<yellowstone_grpc_proto::tonic::metadata::AsciiMetadataKey, yellowstone_grpc_proto::tonic::metadata::AsciiMetadataValue>
+{
+    fn call(&mut self, mut request: yellowstone_grpc_proto::tonic::Request<()>) -> Result<yellowstone_grpc_proto::tonic::Request<()>, yellowstone_grpc_proto::tonic::Status> {
+        for (key, value) in &self.headers {
+            request.metadata_mut().insert(key.clone(), value.clone());
+        }
+        Ok(request)
+    }
+}
+
+/// PEM contents of the files named by a source's TLS options
+pub struct TlsFiles {
+    pub ca_cert: Option<Vec<u8>>,
+    /// Client certificate and key for mutual TLS
+    pub identity: Option<(Vec<u8>, Vec<u8>)>,
+    pub domain_name: Option<String>,
+}
+
+impl TlsFiles {
+    pub fn load(tls: &TlsConfig) -> Result<Self> {
+        let ca_cert = match &tls.ca_cert {
+            Some(path) => Some(fs::read(path).with_context(|| format!("Failed to read CA certificate {}", path.display()))?),
+            None => None,
+        };
+        
+        let identity = match (&tls.client_cert, &tls.client_key) {
+            (Some(cert_path), Some(key_path)) => {
+                let cert = fs::read(cert_path).with_context(|| format!("Failed to read client certificate {}", cert_path.display()))?;
+                let key = fs::read(key_path).with_context(|| format!("Failed to read client key {}", key_path.display()))?;
+                Some((cert, key))
+            }
+            (None, None) => None,
+            _ => return Err(anyhow::anyhow!("Both client_cert and client_key are required for mutual TLS")),
+        };
+        
+        Ok(Self { ca_cert, identity, domain_name: tls.domain_name.clone() })
+    }
+}
+
+/// Sends pre-encoded requests and hands every response over as the protobuf bytes read off the wire,
+/// so a message can be recorded exactly as received and decoded by the caller
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RawCodec;
+
+impl tonic::codec::Codec for RawCodec {
+    type Encode = Bytes;
+    type Decode = Bytes;
+    type Encoder = RawCodec;
+    type Decoder = RawCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        RawCodec
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        RawCodec
+    }
+}
+
+impl tonic::codec::Encoder for RawCodec {
+    type Item = Bytes;
+    type Error = tonic::Status;
+
+    fn encode(&mut self, item: Bytes, dst: &mut tonic::codec::EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put(item);
+        Ok(())
+    }
+}
+
+impl tonic::codec::Decoder for RawCodec {
+    type Item = Bytes;
+    type Error = tonic::Status;
+
+    fn decode(&mut self, src: &mut tonic::codec::DecodeBuf<'_>) -> Result<Option<Bytes>, Self::Error> {
+        Ok(Some(src.copy_to_bytes(src.remaining())))
+    }
+}
+
+impl yellowstone_grpc_proto::tonic::codec::Codec for RawCodec {
+    type Encode = Bytes;
+    type Decode = Bytes;
+    type Encoder = RawCodec;
+    type Decoder = RawCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        RawCodec
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        RawCodec
+    }
+}
+
+impl yellowstone_grpc_proto::tonic::codec::Encoder for RawCodec {
+    type Item = Bytes;
+    type Error = yellowstone_grpc_proto::tonic::Status;
+
+    fn encode(&mut self, item: Bytes, dst: &mut yellowstone_grpc_proto::tonic::codec::EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put(item);
+        Ok(())
+    }
+}
+
+impl yellowstone_grpc_proto::tonic::codec::Decoder for RawCodec {
+    type Item = Bytes;
+    type Error = yellowstone_grpc_proto::tonic::Status;
+
+    fn decode(&mut self, src: &mut yellowstone_grpc_proto::tonic::codec::DecodeBuf<'_>) -> Result<Option<Bytes>, Self::Error> {
+        Ok(Some(src.copy_to_bytes(src.remaining())))
+    }
+}
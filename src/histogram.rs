@@ -0,0 +1,268 @@
+use hdrhistogram::Histogram;
+
+/// Significant decimal digits kept by every latency histogram
+const SIGNIFICANT_DIGITS: u8 = 3;
+
+/// Streaming distribution of signed latency differences (ns) in bounded memory.
+/// Magnitudes of non-negative and negative values go to separate HDR histograms.
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    positive: Histogram<u64>,
+    negative: Histogram<u64>,
+    sum: i128,
+    min: i64,
+    max: i64,
+    tie_tolerance_ns: i64,
+    wins: u64,
+    ties: u64,
+    losses: u64,
+}
+
+impl LatencyHistogram {
+    /// Values within `tie_tolerance_ns` of zero are counted as ties instead of wins or losses
+    pub fn new(tie_tolerance_ns: i64) -> Self {
+        let histogram = Histogram::new(SIGNIFICANT_DIGITS).expect("valid histogram precision");
+        
+        Self {
+            positive: histogram.clone(),
+            negative: histogram,
+            sum: 0,
+            min: i64::MAX,
+            max: i64::MIN,
+            tie_tolerance_ns,
+            wins: 0,
+            ties: 0,
+            losses: 0,
+        }
+    }
+
+    pub fn record(&mut self, value: i64) {
+        let histogram = if value >= 0 { &mut self.positive } else { &mut self.negative };
+        histogram.record(value.unsigned_abs()).expect("auto-resizing latency histogram rejected a value below 2^63");
+        
+        self.sum += value as i128;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        
+        if value > self.tie_tolerance_ns {
+            self.wins += 1;
+        } else if value < -self.tie_tolerance_ns {
+            self.losses += 1;
+        } else {
+            self.ties += 1;
+        }
+    }
+
+    /// Add every value recorded by `other`
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        self.positive.add(&other.positive).expect("auto-resizing latency histogram rejected a merge");
+        self.negative.add(&other.negative).expect("auto-resizing latency histogram rejected a merge");
+        
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.wins += other.wins;
+        self.ties += other.ties;
+        self.losses += other.losses;
+    }
+    
+    pub fn len(&self) -> u64 {
+        self.positive.len() + self.negative.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.is_empty() { 0.0 } else { self.sum as f64 / self.len() as f64 }
+    }
+
+    pub fn min(&self) -> i64 {
+        if self.is_empty() { 0 } else { self.min }
+    }
+
+    pub fn max(&self) -> i64 {
+        if self.is_empty() { 0 } else { self.max }
+    }
+
+    pub fn tie_tolerance_ns(&self) -> i64 {
+        self.tie_tolerance_ns
+    }
+
+    /// Values above the tie tolerance
+    pub fn wins(&self) -> u64 {
+        self.wins
+    }
+
+    pub fn ties(&self) -> u64 {
+        self.ties
+    }
+
+    /// Values below the negative tie tolerance
+    pub fn losses(&self) -> u64 {
+        self.losses
+    }
+
+    pub fn median(&self) -> f64 {
+        median_of(&self.buckets(), self.len())
+    }
+
+    /// Nearest-rank percentile, `quantile` in 0..1
+    pub fn percentile(&self, quantile: f64) -> f64 {
+        percentile_of(&self.buckets(), self.len(), quantile)
+    }
+
+    /// Recorded values as (representative value, count), in ascending order
+    pub fn buckets(&self) -> Vec<(i64, u64)> {
+        let mut buckets: Vec<(i64, u64)> = self.negative
+            .iter_recorded()
+            .map(|bucket| (-(self.negative.median_equivalent(bucket.value_iterated_to()) as i64), bucket.count_at_value()))
+            .collect();
+        buckets.reverse();
+        
+        buckets.extend(
+            self.positive
+                .iter_recorded()
+                .map(|bucket| (self.positive.median_equivalent(bucket.value_iterated_to()) as i64, bucket.count_at_value())),
+        );
+        
+        // The extremes are tracked exactly
+        if let Some(first) = buckets.first_mut() {
+            first.0 = first.0.max(self.min);
+        }
+        if let Some(last) = buckets.last_mut() {
+            last.0 = last.0.min(self.max);
+        }
+        buckets
+    }
+
+    /// Recorded magnitudes as (magnitude, non-negative count, negative count), in ascending order
+    pub fn signed_magnitudes(&self) -> Vec<(u64, u64, u64)> {
+        let mut magnitudes: Vec<(u64, u64, u64)> = Vec::new();
+        let positive = self.positive.iter_recorded().map(|bucket| (self.positive.median_equivalent(bucket.value_iterated_to()), bucket.count_at_value(), 0));
+        let negative = self.negative.iter_recorded().map(|bucket| (self.negative.median_equivalent(bucket.value_iterated_to()), 0, bucket.count_at_value()));
+        
+        let mut all: Vec<(u64, u64, u64)> = positive.chain(negative).collect();
+        all.sort_unstable_by_key(|(magnitude, _, _)| *magnitude);
+        for (magnitude, positives, negatives) in all {
+            match magnitudes.last_mut() {
+                Some(last) if last.0 == magnitude => {
+                    last.1 += positives;
+                    last.2 += negatives;
+                }
+                _ => magnitudes.push((magnitude, positives, negatives)),
+            }
+        }
+        magnitudes
+    }
+}
+
+/// Value at a 0-based rank of ascending `buckets`
+pub fn value_at_rank(buckets: &[(i64, u64)], rank: u64) -> i64 {
+    let mut seen = 0;
+    for &(value, count) in buckets {
+        seen += count;
+        if seen > rank {
+            return value;
+        }
+    }
+    buckets.last().map_or(0, |&(value, _)| value)
+}
+
+/// Median of `len` bucketed values; the mean of the two middle values for even lengths
+pub fn median_of(buckets: &[(i64, u64)], len: u64) -> f64 {
+    if len == 0 {
+        return 0.0;
+    }
+    
+    if len.is_multiple_of(2) {
+        (value_at_rank(buckets, len / 2 - 1) + value_at_rank(buckets, len / 2)) as f64 / 2.0
+    } else {
+        value_at_rank(buckets, len / 2) as f64
+    }
+}
+
+/// Nearest-rank percentile of `len` bucketed values
+pub fn percentile_of(buckets: &[(i64, u64)], len: u64, quantile: f64) -> f64 {
+    if len == 0 {
+        return 0.0;
+    }
+    
+    let rank = ((len as f64 * quantile) as u64).min(len - 1);
+    value_at_rank(buckets, rank) as f64
+}
+
+/// Histogram of `values`, shared by the unit tests
+#[cfg(test)]
+pub(crate) fn histogram_of(values: &[i64], tie_tolerance_ns: i64) -> LatencyHistogram {
+    let mut histogram = LatencyHistogram::new(tie_tolerance_ns);
+    for &value in values {
+        histogram.record(value);
+    }
+    histogram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_and_percentiles_across_zero() {
+        let histogram = histogram_of(&[4, -1, 2, -3], 0);
+        assert_eq!(histogram.buckets(), vec![(-3, 1), (-1, 1), (2, 1), (4, 1)]);
+        assert_eq!(histogram.median(), 0.5);
+        assert_eq!(histogram.percentile(0.0), -3.0);
+        assert_eq!(histogram.percentile(0.25), -1.0);
+        assert_eq!(histogram.percentile(0.5), 2.0);
+        assert_eq!(histogram.percentile(1.0), 4.0);
+        assert_eq!(histogram.mean(), 0.5);
+        assert_eq!((histogram.min(), histogram.max()), (-3, 4));
+    }
+
+    #[test]
+    fn odd_length_median_is_the_middle_value() {
+        let histogram = histogram_of(&[-5, -2, 0, 3, 8], 0);
+        assert_eq!(histogram.median(), 0.0);
+        assert_eq!(histogram.percentile(0.95), 8.0);
+    }
+
+    #[test]
+    fn empty_histogram_reports_zeros() {
+        let histogram = LatencyHistogram::new(0);
+        assert!(histogram.is_empty());
+        assert_eq!((histogram.median(), histogram.percentile(0.95), histogram.mean()), (0.0, 0.0, 0.0));
+        assert_eq!((histogram.min(), histogram.max()), (0, 0));
+    }
+
+    #[test]
+    fn tie_tolerance_splits_wins_ties_and_losses() {
+        let histogram = histogram_of(&[-3, -1, 0, 1, 2, 4], 1);
+        assert_eq!((histogram.wins(), histogram.ties(), histogram.losses()), (2, 3, 1));
+    }
+
+    #[test]
+    fn merge_equals_recording_everything_once() {
+        let mut merged = histogram_of(&[-3, 2], 1);
+        merged.merge(&histogram_of(&[-1, 4], 1));
+        let whole = histogram_of(&[-3, -1, 2, 4], 1);
+        
+        assert_eq!(merged.buckets(), whole.buckets());
+        assert_eq!((merged.len(), merged.mean(), merged.min(), merged.max()), (whole.len(), whole.mean(), whole.min(), whole.max()));
+        assert_eq!((merged.wins(), merged.ties(), merged.losses()), (whole.wins(), whole.ties(), whole.losses()));
+    }
+
+    #[test]
+    fn signed_magnitudes_pair_up_opposite_signs() {
+        let histogram = histogram_of(&[-2, -1, 1, 1, 3], 0);
+        assert_eq!(histogram.signed_magnitudes(), vec![(1, 2, 1), (2, 0, 1), (3, 1, 0)]);
+    }
+
+    #[test]
+    fn large_values_keep_three_significant_digits() {
+        let histogram = histogram_of(&[12_345_678], 0);
+        let value = histogram.buckets()[0].0;
+        assert!((value - 12_345_678).abs() <= 12_345_678 / 1_000, "{} is too far from 12345678", value);
+        assert_eq!(histogram.median(), value as f64);
+    }
+}
@@ -0,0 +1,239 @@
+//! Latency broken down by the slot leader, from a `getLeaderSchedule` dump and an optional region map.
+
+use anyhow::{Context, Result};
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Slots per epoch on mainnet-beta, used to place slots when the epoch's first slot is not given
+pub const SLOTS_PER_EPOCH: u64 = 432_000;
+
+/// Leaders shown in the table and Markdown reports; JSON has all of them
+pub const TOP_LEADERS: usize = 20;
+
+/// Per-group lags keep two significant digits in microseconds, a few KB per leader and source
+const LAG_SIGNIFICANT_DIGITS: u8 = 2;
+
+/// Which validator leads each slot of one epoch, and optionally where it is
+pub struct LeaderSchedule {
+    identities: Vec<String>,
+    /// Slot index within the epoch -> index into `identities`
+    slot_leaders: Vec<Option<u32>>,
+    /// First slot of the epoch; without it slots are placed by `slot % SLOTS_PER_EPOCH`
+    first_slot: Option<u64>,
+    regions: HashMap<String, String>,
+}
+
+impl LeaderSchedule {
+    /// Load a `getLeaderSchedule` result (identity -> slot indices), bare or wrapped in a JSON-RPC response
+    pub fn load(path: &Path, first_slot: Option<u64>) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read leader schedule {}", path.display()))?;
+        let value: Value = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid leader schedule JSON {}", path.display()))?;
+        let schedule = value.get("result").unwrap_or(&value);
+        let schedule: HashMap<String, Vec<u64>> = serde_json::from_value(schedule.clone())
+            .with_context(|| format!("{} is not a leader schedule (identity -> slot indices)", path.display()))?;
+        
+        let mut identities = Vec::with_capacity(schedule.len());
+        let mut slot_leaders: Vec<Option<u32>> = Vec::new();
+        for (identity, slots) in schedule {
+            let index = identities.len() as u32;
+            identities.push(identity);
+            for slot in slots {
+                if slot >= SLOTS_PER_EPOCH {
+                    return Err(anyhow::anyhow!("Leader schedule {} lists slot index {} for {}, outside an epoch (0..{}); slot indices must be relative to the epoch start",
+                        path.display(), slot, identities[index as usize], SLOTS_PER_EPOCH));
+                }
+                let slot = slot as usize;
+                if slot >= slot_leaders.len() {
+                    slot_leaders.resize(slot + 1, None);
+                }
+                slot_leaders[slot] = Some(index);
+            }
+        }
+        
+        Ok(Self { identities, slot_leaders, first_slot, regions: HashMap::new() })
+    }
+
+    /// Attach a validator identity -> region map (JSON, or TOML/YAML by extension)
+    pub fn with_regions(mut self, path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read validator regions {}", path.display()))?;
+        
+        self.regions = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&contents)
+                .with_context(|| format!("Invalid YAML region map {}", path.display()))?,
+            Some("toml") => toml::from_str(&contents)
+                .with_context(|| format!("Invalid TOML region map {}", path.display()))?,
+            _ => serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid JSON region map {}", path.display()))?,
+        };
+        Ok(self)
+    }
+
+    pub fn leader_count(&self) -> usize {
+        self.identities.len()
+    }
+
+    pub fn has_regions(&self) -> bool {
+        !self.regions.is_empty()
+    }
+
+    pub fn leader(&self, slot: u64) -> Option<&str> {
+        let index = match self.first_slot {
+            Some(first_slot) => slot.checked_sub(first_slot)?,
+            None => slot % SLOTS_PER_EPOCH,
+        };
+        let leader = (*self.slot_leaders.get(index as usize)?)?;
+        Some(&self.identities[leader as usize])
+    }
+
+    pub fn region(&self, identity: &str) -> Option<&str> {
+        self.regions.get(identity).map(|region| region.as_str())
+    }
+}
+
+/// Lag of each source behind the earliest arrival, per leader and per region
+#[derive(Debug, Clone, Default)]
+pub struct LeaderTotals {
+    by_leader: HashMap<String, GroupTotals>,
+    by_region: HashMap<String, GroupTotals>,
+    /// Transactions without a slot or outside the schedule
+    unknown_leader: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct GroupTotals {
+    transactions: usize,
+    first_arrivals: HashMap<String, usize>,
+    /// Lag behind the earliest arrival in microseconds, coarse since there is one per leader and source
+    lags: HashMap<String, Histogram<u32>>,
+}
+
+impl GroupTotals {
+    fn record(&mut self, lags: &[(&str, u64)], tie_tolerance_ns: i64) {
+        self.transactions += 1;
+        for &(source, lag) in lags {
+            if lag <= tie_tolerance_ns as u64 {
+                *self.first_arrivals.entry(source.to_string()).or_default() += 1;
+            }
+            self.lags
+                .entry(source.to_string())
+                .or_insert_with(lag_histogram)
+                .record(lag / 1_000)
+                .expect("auto-resizing lag histogram rejected a lag");
+        }
+    }
+
+    fn merge(&mut self, other: &GroupTotals) {
+        self.transactions += other.transactions;
+        for (source, count) in &other.first_arrivals {
+            *self.first_arrivals.entry(source.clone()).or_default() += count;
+        }
+        for (source, lags) in &other.lags {
+            match self.lags.get_mut(source) {
+                Some(existing) => existing.add(lags).expect("auto-resizing lag histogram rejected a merge"),
+                None => {
+                    self.lags.insert(source.clone(), lags.clone());
+                }
+            }
+        }
+    }
+
+    fn stats(&self, group: &str, source_names: &[String]) -> GroupStats {
+        let sources = source_names
+            .iter()
+            .map(|name| {
+                let lags = self.lags.get(name).cloned().unwrap_or_else(lag_histogram);
+                let first = self.first_arrivals.get(name).copied().unwrap_or(0);
+                
+                GroupSourceStats {
+                    source: name.clone(),
+                    seen: lags.len() as usize,
+                    first_arrival_percentage: if self.transactions > 0 { first as f64 / self.transactions as f64 * 100.0 } else { 0.0 },
+                    median_lag_ms: lags.value_at_quantile(0.5) as f64 / 1_000.0,
+                    p95_lag_ms: lags.value_at_quantile(0.95) as f64 / 1_000.0,
+                }
+            })
+            .collect();
+        
+        GroupStats { group: group.to_string(), transactions: self.transactions, sources }
+    }
+}
+
+fn lag_histogram() -> Histogram<u32> {
+    Histogram::new(LAG_SIGNIFICANT_DIGITS).expect("valid histogram precision")
+}
+
+impl LeaderTotals {
+    /// Record a finished transaction seen by several sources; `lags` is each source's lag behind the earliest arrival
+    pub fn record(&mut self, schedule: &LeaderSchedule, slot: Option<u64>, lags: &[(&str, u64)], tie_tolerance_ns: i64) {
+        let Some(leader) = slot.and_then(|slot| schedule.leader(slot)) else {
+            self.unknown_leader += 1;
+            return;
+        };
+        
+        self.by_leader.entry(leader.to_string()).or_default().record(lags, tie_tolerance_ns);
+        if schedule.has_regions() {
+            let region = schedule.region(leader).unwrap_or("unknown");
+            self.by_region.entry(region.to_string()).or_default().record(lags, tie_tolerance_ns);
+        }
+    }
+
+    pub fn merge(&mut self, other: &LeaderTotals) {
+        for (leader, totals) in &other.by_leader {
+            self.by_leader.entry(leader.clone()).or_default().merge(totals);
+        }
+        for (region, totals) in &other.by_region {
+            self.by_region.entry(region.clone()).or_default().merge(totals);
+        }
+        self.unknown_leader += other.unknown_leader;
+    }
+
+    pub fn breakdown(&self, source_names: &[String]) -> LeaderBreakdown {
+        let sorted = |groups: &HashMap<String, GroupTotals>| {
+            let mut stats: Vec<GroupStats> = groups.iter().map(|(group, totals)| totals.stats(group, source_names)).collect();
+            stats.sort_by(|a, b| b.transactions.cmp(&a.transactions).then(a.group.cmp(&b.group)));
+            stats
+        };
+        
+        LeaderBreakdown {
+            by_leader: sorted(&self.by_leader),
+            by_region: sorted(&self.by_region),
+            unknown_leader_transactions: self.unknown_leader,
+        }
+    }
+}
+
+/// Per-source lag behind the earliest arrival, grouped by slot leader and by the leader's region
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderBreakdown {
+    /// Most transactions first
+    pub by_leader: Vec<GroupStats>,
+    /// Empty without a region map; leaders missing from it are grouped as "unknown"
+    pub by_region: Vec<GroupStats>,
+    /// Transactions seen by several sources whose leader is not in the schedule
+    pub unknown_leader_transactions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupStats {
+    /// Leader identity or region
+    pub group: String,
+    pub transactions: usize,
+    pub sources: Vec<GroupSourceStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupSourceStats {
+    pub source: String,
+    pub seen: usize,
+    /// Share of the group's transactions this source delivered first (within the tie tolerance)
+    pub first_arrival_percentage: f64,
+    pub median_lag_ms: f64,
+    pub p95_lag_ms: f64,
+}
@@ -0,0 +1,16 @@
+pub mod benchmark;
+pub mod config;
+pub mod geyser_client;
+pub mod grpc;
+pub mod histogram;
+pub mod leaders;
+pub mod metrics;
+pub mod mock;
+pub mod output;
+pub mod reconnect;
+pub mod recording;
+pub mod report;
+pub mod shredlink_client;
+pub mod slots;
+pub mod source;
+pub mod stats;
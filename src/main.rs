@@ -2,30 +2,170 @@ use anyhow::Result;
 use clap::Parser;
 use console::Style;
 use dotenv::dotenv;
-use std::collections::HashMap;
-use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
-mod benchmark;
-mod geyser_client;
-mod shredlink_client;
-
-use benchmark::Benchmark;
+use shredlink_benchmarking::benchmark::{Benchmark, DaemonReports, Warmup, DEFAULT_MATCH_WINDOW};
+use shredlink_benchmarking::config::{Commitment, Config, SourceConfig, SourceKind, TransactionFilter};
+use shredlink_benchmarking::leaders::LeaderSchedule;
+use shredlink_benchmarking::metrics::{self, Metrics};
+use shredlink_benchmarking::output;
+use shredlink_benchmarking::reconnect::ReconnectPolicy;
+use shredlink_benchmarking::recording::{Recorder, Recording};
+use shredlink_benchmarking::report::{DumpFormat, ObservationWriter, ReportFormat};
+use shredlink_benchmarking::source::StreamSource;
+use shredlink_benchmarking::status;
 
 #[derive(Parser)]
 #[command(name = "shredlink")]
-#[command(about = "A high-performance benchmarking tool for comparing multiple Geyser sources and Shredlink transaction streaming latency on Solana")]
+#[command(about = "A high-performance benchmarking tool for comparing transaction streaming latency of multiple Solana feeds")]
 #[command(version = "1.0")]
 struct Cli {
-    /// Benchmark duration in seconds
-    #[arg(short, long, default_value_t = 60)]
-    duration: u64,
+    /// Configuration file (TOML or YAML) with sources and run settings
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+    
+    /// Benchmark duration in seconds [default: 60]
+    #[arg(short, long)]
+    duration: Option<u64>,
+    
+    /// Run until stopped, reconnecting as needed, and report every --report-interval
+    #[arg(long, conflicts_with = "duration")]
+    daemon: bool,
+    
+    /// Seconds covered by each daemon report [default: 300]
+    #[arg(long)]
+    report_interval: Option<u64>,
+    
+    /// Subscribe this Geyser source at processed and confirmed at once and measure the delay between them
+    #[arg(long, conflicts_with_all = ["baseline", "replay"])]
+    commitment_delay: Option<String>,
+    
+    /// Serve Prometheus metrics at http://<address>/metrics, e.g. 0.0.0.0:9100
+    #[arg(long)]
+    metrics_listen: Option<String>,
+    
+    /// Discard observations for this many seconds, and until every source has delivered a message
+    #[arg(short, long)]
+    warmup: Option<u64>,
+    
+    /// Start measuring after this many seconds even if a source is still silent [default: 30]
+    #[arg(long)]
+    warmup_timeout: Option<u64>,
+    
+    /// Source every other source is compared against (defaults to the first Shredlink source)
+    #[arg(short, long)]
+    baseline: Option<String>,
+    
+    /// Rank first arrivals over transactions seen by at least this many sources [default: all sources]
+    #[arg(short, long)]
+    quorum: Option<usize>,
+    
+    /// Count arrival differences up to this many milliseconds as ties [default: 0]
+    #[arg(long)]
+    tie_tolerance_ms: Option<f64>,
+    
+    /// Seconds other sources get to deliver a transaction after its first arrival before it is finalized [default: 30]
+    #[arg(long)]
+    match_window: Option<u64>,
+    
+    /// Break latency down by slot leader using this getLeaderSchedule result (JSON)
+    #[arg(long)]
+    leader_schedule: Option<PathBuf>,
+    
+    /// First slot of the schedule's epoch [default: slot modulo 432000]
+    #[arg(long)]
+    epoch_first_slot: Option<u64>,
+    
+    /// Validator identity -> region map (JSON, TOML or YAML) to also group leaders by region
+    #[arg(long)]
+    validator_regions: Option<PathBuf>,
+    
+    /// Report format [default: table]
+    #[arg(short, long, value_enum)]
+    format: Option<ReportFormat>,
+    
+    /// Write the report to this file instead of stdout (json, csv and markdown formats)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    
+    /// Write every observed transaction with per-source arrival timestamps to this file
+    #[arg(long)]
+    dump: Option<PathBuf>,
+    
+    /// Format of the observation dump [default: jsonl]
+    #[arg(long, value_enum)]
+    dump_format: Option<DumpFormat>,
+    
+    /// Record every received message with its receive time to this file for later replay
+    #[arg(long, conflicts_with = "replay")]
+    record: Option<PathBuf>,
+    
+    /// Replay a recording instead of connecting to the configured sources
+    #[arg(long)]
+    replay: Option<PathBuf>,
+    
+    /// Give up on a source after this many consecutive reconnect attempts (retries forever by default)
+    #[arg(long)]
+    max_reconnects: Option<u32>,
+    
+    /// Delay before the first reconnect attempt, in milliseconds [default: 500]
+    #[arg(long)]
+    reconnect_backoff_ms: Option<u64>,
+    
+    /// Upper bound for the exponential reconnect delay, in milliseconds [default: 30000]
+    #[arg(long)]
+    reconnect_max_backoff_ms: Option<u64>,
+    
+    /// Random spread applied to each reconnect delay, as a fraction of it [default: 0.2]
+    #[arg(long)]
+    reconnect_jitter: Option<f64>,
+    
+    /// Only transactions touching any of these accounts (overrides config filters)
+    #[arg(long, value_delimiter = ',')]
+    account_include: Vec<String>,
+    
+    /// Drop transactions touching any of these accounts (overrides config filters)
+    #[arg(long, value_delimiter = ',')]
+    account_exclude: Vec<String>,
+    
+    /// Only transactions touching all of these accounts (overrides config filters)
+    #[arg(long, value_delimiter = ',')]
+    account_required: Vec<String>,
+    
+    /// Only vote transactions (true) or no vote transactions (false); defaults to false with other filter flags
+    #[arg(long)]
+    vote: Option<bool>,
+    
+    /// Only failed transactions (true) or no failed transactions (false); defaults to false with other filter flags
+    #[arg(long)]
+    failed: Option<bool>,
+}
+
+impl Cli {
+    /// Filter built from the command line, if any filter flag was given
+    fn transaction_filter(&self) -> Option<TransactionFilter> {
+        let any_flag = !self.account_include.is_empty()
+            || !self.account_exclude.is_empty()
+            || !self.account_required.is_empty()
+            || self.vote.is_some()
+            || self.failed.is_some();
+        
+        any_flag.then(|| TransactionFilter {
+            account_include: self.account_include.clone(),
+            account_exclude: self.account_exclude.clone(),
+            account_required: self.account_required.clone(),
+            vote: Some(self.vote.unwrap_or(false)),
+            failed: Some(self.failed.unwrap_or(false)),
+        })
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
+    // Initialize logging; stdout may be reserved for the report
+    tracing_subscriber::fmt().with_writer(std::io::stderr).init();
     
     // Load environment variables
     dotenv().ok();
@@ -36,58 +176,279 @@ async fn main() -> Result<()> {
     let green = Style::new().green();
     let red = Style::new().red();
     
-    println!("{}", cyan.apply_to("🚀 ShredLink - Multi-Geyser Benchmark"));
-    println!("{}", cyan.apply_to("=".repeat(45)));
-    
-    // Get Geyser configuration from environment
-    let mut geyser_urls = HashMap::new();
-    let mut geyser_tokens = HashMap::new();
-    
-    for (key, value) in env::vars() {
-        if key.starts_with("GEYSER_") && key.ends_with("_URL") && key != "GEYSER_HOST_URL" {
-            if let Some(name_part) = key.strip_prefix("GEYSER_").and_then(|s| s.strip_suffix("_URL")) {
-                let geyser_name = format!("Geyser-{}", name_part.to_lowercase());
-                geyser_urls.insert(geyser_name.clone(), value);
-                
-                // Check for corresponding token
-                let token_key = format!("GEYSER_{}_TOKEN", name_part);
-                if let Ok(token) = env::var(&token_key) {
-                    geyser_tokens.insert(geyser_name, token);
-                }
+    // Sources come from the config file, falling back to environment variables
+    let mut config = match &cli.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    
+    // A machine-readable report on stdout has to be the only thing there
+    let machine_format = cli.format.or(config.run.format).is_some_and(|format| format != ReportFormat::Table);
+    let has_output = cli.output.is_some() || config.run.output.is_some();
+    if machine_format && !has_output {
+        output::reserve_stdout();
+    }
+    
+    // The table is printed as the run goes, so it cannot be written to a file
+    if !machine_format && has_output {
+        return Err(anyhow::anyhow!("{}", red.apply_to("❌ --output needs --format json, csv or markdown; the table report is only printed")));
+    }
+    
+    status!("{}", cyan.apply_to("🚀 ShredLink - Multi-Source Benchmark"));
+    status!("{}", cyan.apply_to("=".repeat(45)));
+    
+    // A replay takes its sources from the recording
+    let recording = cli.replay.as_deref().map(Recording::load).transpose()?;
+    if let Some(recording) = &recording {
+        let location = format!("replay of {}", cli.replay.as_ref().unwrap().display());
+        config.sources = recording.sources
+            .iter()
+            .map(|source| SourceConfig::new(source.name.clone(), source.kind, location.clone()))
+            .collect();
+    }
+    if config.sources.is_empty() {
+        config.sources = Config::from_env().sources;
+    }
+    
+    // One source against itself: processed arrivals are the baseline for confirmed ones
+    let commitment_delay = if recording.is_some() { None } else { cli.commitment_delay.clone().or(config.run.commitment_delay.take()) };
+    if let Some(name) = &commitment_delay {
+        let source = config.sources
+            .iter()
+            .find(|source| source.name == *name)
+            .ok_or_else(|| anyhow::anyhow!("{}", red.apply_to(format!("❌ Commitment delay source '{}' is not configured", name))))?;
+        if source.kind != SourceKind::Geyser {
+            return Err(anyhow::anyhow!("{}", red.apply_to(format!("❌ Commitment delay needs a Geyser source; '{}' has no commitment levels", name))));
+        }
+        
+        config.sources = [Commitment::Processed, Commitment::Confirmed]
+            .into_iter()
+            .map(|commitment| {
+                let mut copy = source.clone();
+                copy.name = format!("{}@{}", name, commitment.as_str());
+                copy.commitment = commitment;
+                // Both subscriptions would announce the same slots
+                copy.slot_updates = Some(commitment == Commitment::Processed && source.slot_updates != Some(false));
+                copy
+            })
+            .collect();
+    }
+    
+    if config.sources.len() < 2 {
+        return Err(anyhow::anyhow!("{}", red.apply_to("❌ At least two sources are required. Define them in a --config file or set GEYSER_<NAME>_URL and/or SHREDLINK_HOST_URL environment variables")));
+    }
+    
+    // Command-line filter flags replace every configured filter
+    let cli_filter = cli.transaction_filter();
+    for source in &mut config.sources {
+        if cli_filter.is_some() {
+            source.filter = cli_filter.clone();
+        } else if source.filter.is_none() {
+            source.filter = config.run.filter.clone();
+        }
+    }
+    
+    // Command-line flags take precedence over the config file
+    let run = config.run;
+    
+    // A replay runs until its last message by default
+    let default_duration = recording.as_ref().map_or(60, |recording| recording.duration_ns().div_ceil(1_000_000_000) + 1);
+    let duration = cli.duration.or(run.duration).unwrap_or(default_duration);
+    let warmup = cli.warmup.or(run.warmup).map(|seconds| Warmup {
+        duration: Duration::from_secs(seconds),
+        timeout: Duration::from_secs(cli.warmup_timeout.or(run.warmup_timeout).unwrap_or(30)),
+    });
+    let match_window = cli.match_window.or(run.match_window).map(Duration::from_secs).unwrap_or(DEFAULT_MATCH_WINDOW);
+    if match_window.is_zero() {
+        return Err(anyhow::anyhow!("{}", red.apply_to("❌ Match window must be at least one second")));
+    }
+    let format = cli.format.or(run.format).unwrap_or(ReportFormat::Table);
+    let output = cli.output.or(run.output);
+    let daemon = (cli.daemon || run.daemon.unwrap_or(false)).then(|| DaemonReports {
+        interval: Duration::from_secs(cli.report_interval.or(run.report_interval).unwrap_or(300)),
+        format,
+        output: output.clone(),
+    });
+    if daemon.as_ref().is_some_and(|daemon| daemon.interval.is_zero()) {
+        return Err(anyhow::anyhow!("{}", red.apply_to("❌ Report interval must be at least one second")));
+    }
+    let dump = cli.dump.or(run.dump);
+    let dump_format = cli.dump_format.or(run.dump_format).unwrap_or(DumpFormat::Jsonl);
+    let metrics_listen = cli.metrics_listen.or(run.metrics_listen);
+    let record = if recording.is_some() { None } else { cli.record.or(run.record) };
+    // The schedule options may come from the command line or the config, so they are checked once merged
+    let epoch_first_slot = cli.epoch_first_slot.or(run.epoch_first_slot);
+    let validator_regions = cli.validator_regions.or(run.validator_regions);
+    let leader_schedule = match cli.leader_schedule.or(run.leader_schedule) {
+        Some(path) => {
+            let schedule = LeaderSchedule::load(&path, epoch_first_slot)?;
+            match validator_regions {
+                Some(regions) => Some(schedule.with_regions(&regions)?),
+                None => Some(schedule),
             }
         }
+        None if epoch_first_slot.is_some() || validator_regions.is_some() => {
+            return Err(anyhow::anyhow!("{}", red.apply_to("❌ epoch_first_slot and validator_regions need a leader schedule")));
+        }
+        None => None,
+    };
+    
+    let baseline = commitment_delay
+        .as_ref()
+        .map(|name| format!("{}@{}", name, Commitment::Processed.as_str()))
+        .or(cli.baseline)
+        .or(run.baseline)
+        .or_else(|| config.sources.iter().find(|source| source.kind == SourceKind::Shredlink).map(|source| source.name.clone()))
+        .unwrap_or_else(|| config.sources[0].name.clone());
+    
+    if !config.sources.iter().any(|source| source.name == baseline) {
+        return Err(anyhow::anyhow!("{}", red.apply_to(format!("❌ Baseline source '{}' is not configured", baseline))));
     }
     
-    if geyser_urls.is_empty() {
-        return Err(anyhow::anyhow!("{}", red.apply_to("❌ No Geyser URLs found. Set GEYSER_<NAME>_URL environment variables")));
+    let quorum = cli.quorum.or(run.quorum).unwrap_or(config.sources.len());
+    if quorum == 0 || quorum > config.sources.len() {
+        return Err(anyhow::anyhow!("{}", red.apply_to(format!("❌ Quorum must be between 1 and the number of sources ({})", config.sources.len()))));
     }
     
-    let shredlink_host = env::var("SHREDLINK_HOST_URL").map_err(|_| {
-        anyhow::anyhow!("{}", red.apply_to("❌ SHREDLINK_HOST_URL environment variable not set"))
-    })?;
+    let tie_tolerance_ms = cli.tie_tolerance_ms.or(run.tie_tolerance_ms).unwrap_or(0.0);
+    if !(tie_tolerance_ms >= 0.0 && tie_tolerance_ms.is_finite()) {
+        return Err(anyhow::anyhow!("{}", red.apply_to("❌ Tie tolerance must be a non-negative number of milliseconds")));
+    }
     
-    let benchmark_time = Duration::from_secs(cli.duration);
+    let benchmark_time = Duration::from_secs(duration);
     
-    println!("{}", green.apply_to("📋 Configuration:"));
-    for (name, url) in &geyser_urls {
-        let auth_status = if geyser_tokens.contains_key(name) { "🔐" } else { "🔓" };
-        println!("  {} {}: {}", auth_status, name, url);
+    status!("{}", green.apply_to("📋 Configuration:"));
+    for source in &config.sources {
+        let auth_status = if source.token.is_some() || !source.headers.is_empty() { "🔐" } else { "🔓" };
+        status!("  {} {}: {}", auth_status, source.name, source.url);
+    }
+    let length = match &daemon {
+        Some(daemon) => format!("Daemon, reporting every {}s", daemon.interval.as_secs()),
+        None => format!("Duration: {}s", duration),
+    };
+    status!("  ⏱️  {} | Sources: {} | Baseline: {} | Quorum: {} | Match window: {}s", length, config.sources.len(), baseline, quorum, match_window.as_secs());
+    if let Some(path) = daemon.as_ref().and_then(|daemon| daemon.output.as_ref()) {
+        status!("  📝 Appending window reports to {}", path.display());
+    }
+    if let Some(warmup) = &warmup {
+        status!("  🔥 Warm-up: {}s (timeout {}s)", warmup.duration.as_secs(), warmup.timeout.as_secs());
+    }
+    if let Some(recording) = &recording {
+        status!("  📼 Replaying {} recorded messages", recording.message_count());
+    }
+    if let Some(path) = &record {
+        status!("  🔴 Recording to {}", path.display());
+    }
+    if let Some(name) = &commitment_delay {
+        status!("  ⏳ Commitment delay: {} at processed vs confirmed", name);
     }
-    println!("  🔗 Shredlink: {}", shredlink_host);
-    println!("  ⏱️  Duration: {}s | Sources: {}", cli.duration, geyser_urls.len());
-    println!();
+    if let Some(schedule) = &leader_schedule {
+        let regions = if schedule.has_regions() { ", grouped by region" } else { "" };
+        status!("  👑 Leader schedule: {} leaders{}", schedule.leader_count(), regions);
+    }
+    if let Some(address) = &metrics_listen {
+        status!("  📡 Metrics at http://{}/metrics", address);
+    }
+    
+    // Shredlink streams shreds, so it cannot know whether a transaction failed
+    let drops_failed = config.sources.iter().any(|source| source.kind == SourceKind::Geyser && source.filter().failed == Some(false));
+    let unfilterable: Vec<String> = config.sources
+        .iter()
+        .filter(|source| recording.is_none() && drops_failed && source.kind == SourceKind::Shredlink && source.filter().failed == Some(false))
+        .map(|source| source.name.clone())
+        .collect();
+    if !unfilterable.is_empty() {
+        status!("  🧹 {} cannot drop failed transactions; those only it delivered are counted apart, not as only seen by it or missed", unfilterable.join(", "));
+    }
+    if recording.is_some() {
+        // The recording keeps no source filters, so failed transactions cannot be told apart
+        status!("  ⚠️  Possibly-failed transactions are not counted apart in a replay; they count as only seen by a source or missed");
+    }
+    status!();
+    
+    // Transactions are written as they are finalized, so the dump needs the source names up front
+    let source_names: Vec<String> = config.sources.iter().map(|source| source.name.clone()).collect();
+    let dump_writer = dump.as_deref().map(|path| ObservationWriter::create(path, dump_format, source_names.clone())).transpose()?;
+    
+    // Bind before connecting anything so a taken port fails fast
+    let metrics = match &metrics_listen {
+        Some(address) => {
+            let listener = tokio::net::TcpListener::bind(address).await
+                .map_err(|e| anyhow::anyhow!("{}", red.apply_to(format!("❌ Cannot serve metrics on {}: {}", address, e))))?;
+            let metrics = Arc::new(Metrics::new(source_names)?);
+            let served = Arc::clone(&metrics);
+            tokio::spawn(async move {
+                if let Err(e) = metrics::serve(served, listener).await {
+                    eprintln!("❌ Metrics server failed: {}", e);
+                }
+            });
+            Some(metrics)
+        }
+        None => None,
+    };
+    
+    // Build the list of sources
+    let recorder = record.as_deref().map(Recorder::create).transpose()?;
+    let sources = match recording {
+        Some(recording) => recording.into_sources(),
+        None => config.sources
+            .into_iter()
+            .map(|source| source.build(recorder.as_ref()))
+            .collect::<Result<Vec<Box<dyn StreamSource>>>>()?,
+    };
     
     // Create and run benchmark
-    let mut benchmark = Benchmark::new(geyser_urls, geyser_tokens, shredlink_host);
+    let defaults = ReconnectPolicy::default();
+    let reconnect_policy = ReconnectPolicy {
+        max_retries: cli.max_reconnects.or(run.reconnect.max_retries),
+        initial_backoff: cli.reconnect_backoff_ms.or(run.reconnect.backoff_ms).map(Duration::from_millis).unwrap_or(defaults.initial_backoff),
+        max_backoff: cli.reconnect_max_backoff_ms.or(run.reconnect.max_backoff_ms).map(Duration::from_millis).unwrap_or(defaults.max_backoff),
+        jitter: cli.reconnect_jitter.or(run.reconnect.jitter).unwrap_or(defaults.jitter),
+    };
+    let mut benchmark = Benchmark::new(sources, baseline, reconnect_policy)
+        .with_quorum(quorum)
+        .with_tie_tolerance(Duration::from_secs_f64(tie_tolerance_ms / 1000.0))
+        .with_match_window(match_window);
+    if let Some(warmup) = warmup {
+        benchmark = benchmark.with_warmup(warmup);
+    }
+    if let Some(writer) = dump_writer {
+        benchmark = benchmark.with_dump(writer);
+    }
+    if let Some(metrics) = metrics {
+        benchmark = benchmark.with_metrics(metrics);
+    }
+    if let Some(schedule) = leader_schedule {
+        benchmark = benchmark.with_leader_schedule(schedule);
+    }
+    if !unfilterable.is_empty() {
+        benchmark = benchmark.with_unfiltered_failures(unfilterable);
+    }
+    let is_daemon = daemon.is_some();
+    if let Some(daemon) = daemon {
+        benchmark = benchmark.with_daemon(daemon);
+    }
     
-    println!("{}", green.apply_to("🏁 Starting benchmark..."));
+    status!("{}", green.apply_to("🏁 Starting benchmark..."));
     benchmark.run(benchmark_time).await?;
     
+    if let (Some(recorder), Some(path)) = (&recorder, &record) {
+        recorder.flush()?;
+        status!("📼 Recording written to {}", path.display());
+    }
+    
     // Print results
-    println!();
-    benchmark.print_report(&"cli.output");
+    status!();
+    if is_daemon {
+        // Window reports already went to the output; finish with the totals for whoever stopped it
+        benchmark.print_report(ReportFormat::Table, None)?;
+    } else {
+        benchmark.print_report(format, output.as_deref())?;
+    }
+    
+    benchmark.finish_dump()?;
     
-    println!("{}", cyan.apply_to("✨ Benchmark completed!"));
+    status!("{}", cyan.apply_to("✨ Benchmark completed!"));
     
     Ok(())
 }
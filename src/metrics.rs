@@ -0,0 +1,171 @@
+//! Prometheus metrics updated while the benchmark runs, served at `/metrics`.
+
+use anyhow::Result;
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// Bucket bounds of the pair latency histograms, in seconds on both sides of zero
+const LATENCY_BUCKETS: [f64; 11] = [0.0005, 0.001, 0.002, 0.005, 0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1.0];
+
+/// Every metric the benchmark exports
+pub struct Metrics {
+    registry: Registry,
+    source_names: Vec<String>,
+    received: IntCounterVec,
+    matched: IntCounterVec,
+    only_seen_by: IntCounterVec,
+    missed: IntCounterVec,
+    reconnects: IntCounterVec,
+    pair_latency: HistogramVec,
+    latest_slot: IntGaugeVec,
+    slot_lag: IntGaugeVec,
+    highest_slot: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new(source_names: Vec<String>) -> Result<Self> {
+        let registry = Registry::new();
+        
+        let received = IntCounterVec::new(
+            Opts::new("shredlink_messages_received_total", "Transactions received from each source, including warm-up"),
+            &["source"],
+        )?;
+        let matched = IntCounterVec::new(
+            Opts::new("shredlink_matched_transactions_total", "Transactions a source shared with the baseline (for the baseline: with any other source)"),
+            &["source"],
+        )?;
+        let only_seen_by = IntCounterVec::new(
+            Opts::new("shredlink_only_seen_by_total", "Finalized transactions seen by this source alone (only-seen-by for the baseline, otherwise not by the baseline)"),
+            &["source"],
+        )?;
+        let missed = IntCounterVec::new(
+            Opts::new("shredlink_missed_transactions_total", "Finalized transactions another source delivered but this one did not"),
+            &["source"],
+        )?;
+        let reconnects = IntCounterVec::new(
+            Opts::new("shredlink_reconnects_total", "Times a source delivered data again after its stream failed"),
+            &["source"],
+        )?;
+        let pair_latency = HistogramVec::new(
+            HistogramOpts::new("shredlink_pair_latency_seconds", "Arrival of `second` minus arrival of `first` (positive means `first` is faster)")
+                .buckets(latency_buckets()),
+            &["first", "second"],
+        )?;
+        let latest_slot = IntGaugeVec::new(
+            Opts::new("shredlink_latest_slot", "Highest slot each source delivered a transaction for"),
+            &["source"],
+        )?;
+        let slot_lag = IntGaugeVec::new(
+            Opts::new("shredlink_slot_lag", "Slots between the highest slot any source delivered and this source's highest slot"),
+            &["source"],
+        )?;
+        
+        registry.register(Box::new(received.clone()))?;
+        registry.register(Box::new(matched.clone()))?;
+        registry.register(Box::new(only_seen_by.clone()))?;
+        registry.register(Box::new(missed.clone()))?;
+        registry.register(Box::new(reconnects.clone()))?;
+        registry.register(Box::new(pair_latency.clone()))?;
+        registry.register(Box::new(latest_slot.clone()))?;
+        registry.register(Box::new(slot_lag.clone()))?;
+        
+        // Every source shows up from the first scrape, even before it delivers anything
+        for name in &source_names {
+            for counter in [&received, &matched, &only_seen_by, &missed, &reconnects] {
+                counter.with_label_values(&[name]);
+            }
+        }
+        
+        Ok(Self {
+            registry,
+            source_names,
+            received,
+            matched,
+            only_seen_by,
+            missed,
+            reconnects,
+            pair_latency,
+            latest_slot,
+            slot_lag,
+            highest_slot: AtomicU64::new(0),
+        })
+    }
+
+    pub fn record_received(&self, source: &str, slot: Option<u64>) {
+        self.received.with_label_values(&[source]).inc();
+        
+        if let Some(slot) = slot {
+            let latest = self.latest_slot.with_label_values(&[source]);
+            if slot as i64 > latest.get() {
+                latest.set(slot as i64);
+            }
+            self.highest_slot.fetch_max(slot, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_matched(&self, source: &str) {
+        self.matched.with_label_values(&[source]).inc();
+    }
+
+    pub fn record_only_seen_by(&self, source: &str) {
+        self.only_seen_by.with_label_values(&[source]).inc();
+    }
+
+    pub fn record_missed(&self, source: &str) {
+        self.missed.with_label_values(&[source]).inc();
+    }
+
+    pub fn record_reconnect(&self, source: &str) {
+        self.reconnects.with_label_values(&[source]).inc();
+    }
+
+    /// Record `diff_ns` (second's arrival minus first's) once per unordered pair, labelled in name order
+    pub fn record_pair(&self, first: &str, second: &str, diff_ns: i64) {
+        let (first, second, diff_ns) = if first <= second { (first, second, diff_ns) } else { (second, first, -diff_ns) };
+        self.pair_latency
+            .with_label_values(&[first, second])
+            .observe(diff_ns as f64 / 1_000_000_000.0);
+    }
+
+    /// Metrics in the Prometheus text format
+    pub fn render(&self) -> Result<String> {
+        // Lags move whenever any source advances, so they are derived at scrape time
+        let highest = self.highest_slot.load(Ordering::Relaxed) as i64;
+        for name in &self.source_names {
+            let latest = self.latest_slot.with_label_values(&[name]).get();
+            if latest > 0 {
+                self.slot_lag.with_label_values(&[name]).set(highest - latest);
+            }
+        }
+        
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+fn latency_buckets() -> Vec<f64> {
+    let mut buckets: Vec<f64> = LATENCY_BUCKETS.iter().rev().map(|bound| -bound).collect();
+    buckets.push(0.0);
+    buckets.extend(LATENCY_BUCKETS);
+    buckets
+}
+
+/// Serve `/metrics` on the listener until the process exits
+pub async fn serve(metrics: Arc<Metrics>, listener: TcpListener) -> Result<()> {
+    let app = Router::new().route("/metrics", get(move || async move {
+        match metrics.render() {
+            Ok(body) => ([(header::CONTENT_TYPE, TextEncoder::new().format_type().to_string())], body).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        }
+    }));
+    
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -0,0 +1,444 @@
+//! Local Yellowstone and Shredlink gRPC servers that replay a scripted transaction stream.
+//!
+//! Every feed follows the same reference timeline, shifted by its own delay and with
+//! individual transactions dropped or sent twice, so end-to-end runs produce known
+//! latency differences without touching the network.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Instant};
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+
+use shredlink_proto::shredlink::shredlink_service_server::{ShredlinkService, ShredlinkServiceServer};
+use shredlink_proto::shredlink::{
+    Entry, SubscribeEntriesRequest, SubscribeTransactionsRequest, SubscribeTransactionsResponse,
+    SubscribeUpdateTransaction as ShredlinkUpdateTransaction, Transaction,
+};
+use yellowstone_grpc_proto::prelude::{
+    geyser_server::{Geyser, GeyserServer}, subscribe_update::UpdateOneof, CommitmentLevel, GetBlockHeightRequest,
+    GetBlockHeightResponse, GetLatestBlockhashRequest, GetLatestBlockhashResponse, GetSlotRequest,
+    GetSlotResponse, GetVersionRequest, GetVersionResponse, IsBlockhashValidRequest,
+    IsBlockhashValidResponse, PingRequest, PongResponse, SubscribeReplayInfoRequest,
+    SubscribeReplayInfoResponse, SlotStatus, SubscribeRequest, SubscribeUpdate, SubscribeUpdateSlot,
+    SubscribeUpdateTransaction, SubscribeUpdateTransactionInfo,
+};
+
+use crate::config::SourceKind;
+
+/// One transaction of the reference timeline
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptedTransaction {
+    /// Base58 signature
+    pub signature: String,
+    pub slot: u64,
+    /// Offset from the scenario start at which the reference timeline emits it
+    pub at_ms: u64,
+}
+
+impl ScriptedTransaction {
+    /// Transaction with a deterministic signature derived from `index`
+    pub fn synthetic(index: u64, slot: u64, at_ms: u64) -> Self {
+        let mut bytes = [0u8; 64];
+        bytes[..8].copy_from_slice(&index.to_be_bytes());
+        bytes[8] = 1;
+        
+        Self {
+            signature: bs58::encode(bytes).into_string(),
+            slot,
+            at_ms,
+        }
+    }
+}
+
+/// Evenly spaced synthetic transactions, four per slot
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyntheticScript {
+    pub count: u64,
+    pub interval_ms: u64,
+    #[serde(default)]
+    pub first_slot: u64,
+}
+
+/// How one feed deviates from the reference timeline
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeedBehavior {
+    /// Added to every transaction's emit time
+    #[serde(default)]
+    pub delay_ms: u64,
+    /// Per-signature delay replacing `delay_ms`
+    #[serde(default)]
+    pub delays_ms: HashMap<String, u64>,
+    /// Signatures this feed never sends
+    #[serde(default)]
+    pub drop: HashSet<String>,
+    /// Signatures this feed sends twice
+    #[serde(default)]
+    pub duplicate: HashSet<String>,
+}
+
+/// One mock server
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockFeedConfig {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: SourceKind,
+    /// Address to listen on, e.g. `127.0.0.1:10000`
+    pub listen: SocketAddr,
+    #[serde(flatten)]
+    pub behavior: FeedBehavior,
+}
+
+/// Scenario file for the mock servers (TOML or YAML)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockScenario {
+    /// Time between server start and the first scripted transaction, leaving clients time to subscribe
+    #[serde(default)]
+    pub start_after_ms: u64,
+    #[serde(default)]
+    pub transactions: Vec<ScriptedTransaction>,
+    /// Generated transactions appended to `transactions`
+    pub synthetic: Option<SyntheticScript>,
+    #[serde(default)]
+    pub feeds: Vec<MockFeedConfig>,
+}
+
+impl MockScenario {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read scenario file {}", path.display()))?;
+        
+        let mut scenario: MockScenario = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&contents)
+                .with_context(|| format!("Invalid YAML scenario {}", path.display()))?,
+            _ => toml::from_str(&contents)
+                .with_context(|| format!("Invalid TOML scenario {}", path.display()))?,
+        };
+        
+        if let Some(synthetic) = scenario.synthetic.take() {
+            for index in 0..synthetic.count {
+                let slot = synthetic.first_slot + index / 4;
+                scenario.transactions.push(ScriptedTransaction::synthetic(index, slot, index * synthetic.interval_ms));
+            }
+        }
+        
+        Ok(scenario)
+    }
+
+    /// Serve every feed until one of the servers fails
+    pub async fn serve(self) -> Result<()> {
+        let start = Instant::now() + Duration::from_millis(self.start_after_ms);
+        let script = Arc::new(self.transactions);
+        let mut servers = Vec::new();
+        
+        for feed_config in self.feeds {
+            let listener = TcpListener::bind(feed_config.listen).await
+                .with_context(|| format!("Failed to listen on {}", feed_config.listen))?;
+            println!("🎭 {} ({:?}) listening on {}", feed_config.name, feed_config.kind, feed_config.listen);
+            
+            let feed = MockFeed::new(script.clone(), feed_config.behavior, start);
+            servers.push(tokio::spawn(serve_feed(feed_config.kind, feed, listener)));
+        }
+        
+        for server in servers {
+            server.await??;
+        }
+        
+        Ok(())
+    }
+}
+
+/// The scripted stream of one feed, anchored to a shared start instant
+#[derive(Debug, Clone)]
+pub struct MockFeed {
+    script: Arc<Vec<ScriptedTransaction>>,
+    behavior: FeedBehavior,
+    start: Instant,
+}
+
+impl MockFeed {
+    pub fn new(script: Arc<Vec<ScriptedTransaction>>, behavior: FeedBehavior, start: Instant) -> Self {
+        Self { script, behavior, start }
+    }
+
+    /// Emit times and transactions of this feed, in emit order
+    pub fn timeline(&self) -> Vec<(Instant, ScriptedTransaction)> {
+        let mut timeline = Vec::new();
+        
+        for transaction in self.script.iter() {
+            if self.behavior.drop.contains(&transaction.signature) {
+                continue;
+            }
+            
+            let delay_ms = self.behavior.delays_ms
+                .get(&transaction.signature)
+                .copied()
+                .unwrap_or(self.behavior.delay_ms);
+            let at = self.start + Duration::from_millis(transaction.at_ms + delay_ms);
+            
+            timeline.push((at, transaction.clone()));
+            if self.behavior.duplicate.contains(&transaction.signature) {
+                timeline.push((at, transaction.clone()));
+            }
+        }
+        
+        timeline.sort_by_key(|(at, _)| *at);
+        timeline
+    }
+
+    /// Send the messages built by `make` for each transaction at its emit time, then hold the stream open until the client leaves
+    fn stream<T, E, F>(&self, mut make: F) -> ReceiverStream<Result<T, E>>
+    where
+        T: Send + 'static,
+        E: Send + 'static,
+        F: FnMut(&ScriptedTransaction) -> Vec<T> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(1024);
+        let timeline = self.timeline();
+        
+        tokio::spawn(async move {
+            for (at, transaction) in timeline {
+                sleep_until(at).await;
+                for message in make(&transaction) {
+                    if tx.send(Ok(message)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            tx.closed().await;
+        });
+        
+        ReceiverStream::new(rx)
+    }
+}
+
+type Status = yellowstone_grpc_proto::tonic::Status;
+
+fn signature_bytes(transaction: &ScriptedTransaction) -> Option<Vec<u8>> {
+    bs58::decode(&transaction.signature).into_vec().ok()
+}
+
+/// A feed announces a slot as processed with its first transaction, and confirms and
+/// finalizes the slots one and two behind it at the same time
+fn slot_updates(slot: u64) -> Vec<SubscribeUpdate> {
+    let statuses = [
+        (Some(slot), SlotStatus::SlotProcessed),
+        (slot.checked_sub(1), SlotStatus::SlotConfirmed),
+        (slot.checked_sub(2), SlotStatus::SlotFinalized),
+    ];
+    
+    statuses
+        .into_iter()
+        .filter_map(|(slot, status)| {
+            Some(SubscribeUpdate {
+                filters: vec!["slots".to_string()],
+                created_at: None,
+                update_oneof: Some(UpdateOneof::Slot(SubscribeUpdateSlot {
+                    slot: slot?,
+                    parent: None,
+                    status: status as i32,
+                    dead_error: None,
+                })),
+            })
+        })
+        .collect()
+}
+
+/// Mock Yellowstone `Geyser` service; only `Subscribe` is implemented, with slot updates if requested.
+/// Transactions are held back until their slot reaches the requested commitment.
+pub struct MockGeyser {
+    feed: MockFeed,
+}
+
+impl MockGeyser {
+    pub fn new(feed: MockFeed) -> Self {
+        Self { feed }
+    }
+}
+
+#[yellowstone_grpc_proto::tonic::async_trait]
+impl Geyser for MockGeyser {
+    type SubscribeStream = ReceiverStream<Result<SubscribeUpdate, Status>>;
+
+    async fn subscribe(
+        &self,
+        request: yellowstone_grpc_proto::tonic::Request<yellowstone_grpc_proto::tonic::Streaming<SubscribeRequest>>,
+    ) -> Result<yellowstone_grpc_proto::tonic::Response<Self::SubscribeStream>, Status> {
+        let subscription = request.into_inner().message().await?.unwrap_or_default();
+        let with_slots = !subscription.slots.is_empty();
+        // Slots behind the newest one that have reached the commitment, as in `slot_updates`
+        let depth = match subscription.commitment.and_then(|level| CommitmentLevel::try_from(level).ok()) {
+            Some(CommitmentLevel::Confirmed) => 1,
+            Some(CommitmentLevel::Finalized) => 2,
+            _ => 0,
+        };
+        
+        let mut current_slot = None;
+        let mut pending: Vec<ScriptedTransaction> = Vec::new();
+        let stream = self.feed.stream(move |transaction| {
+            let mut updates = Vec::new();
+            if current_slot.is_none_or(|slot| transaction.slot > slot) {
+                current_slot = Some(transaction.slot);
+                if with_slots {
+                    updates.extend(slot_updates(transaction.slot));
+                }
+            }
+            
+            pending.push(transaction.clone());
+            let (ready, waiting): (Vec<_>, Vec<_>) = pending.drain(..).partition(|pending| pending.slot + depth <= transaction.slot);
+            pending = waiting;
+            
+            for transaction in ready {
+                let Some(signature) = signature_bytes(&transaction) else {
+                    continue;
+                };
+                updates.push(SubscribeUpdate {
+                    filters: vec!["transactions".to_string()],
+                    created_at: None,
+                    update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+                        transaction: Some(SubscribeUpdateTransactionInfo {
+                            signature,
+                            is_vote: false,
+                            transaction: None,
+                            meta: None,
+                            index: 0,
+                        }),
+                        slot: transaction.slot,
+                    })),
+                });
+            }
+            updates
+        });
+        Ok(yellowstone_grpc_proto::tonic::Response::new(stream))
+    }
+
+    async fn subscribe_replay_info(
+        &self,
+        _request: yellowstone_grpc_proto::tonic::Request<SubscribeReplayInfoRequest>,
+    ) -> Result<yellowstone_grpc_proto::tonic::Response<SubscribeReplayInfoResponse>, Status> {
+        Err(Status::unimplemented("not supported by the mock server"))
+    }
+
+    async fn ping(
+        &self,
+        _request: yellowstone_grpc_proto::tonic::Request<PingRequest>,
+    ) -> Result<yellowstone_grpc_proto::tonic::Response<PongResponse>, Status> {
+        Err(Status::unimplemented("not supported by the mock server"))
+    }
+
+    async fn get_latest_blockhash(
+        &self,
+        _request: yellowstone_grpc_proto::tonic::Request<GetLatestBlockhashRequest>,
+    ) -> Result<yellowstone_grpc_proto::tonic::Response<GetLatestBlockhashResponse>, Status> {
+        Err(Status::unimplemented("not supported by the mock server"))
+    }
+
+    async fn get_block_height(
+        &self,
+        _request: yellowstone_grpc_proto::tonic::Request<GetBlockHeightRequest>,
+    ) -> Result<yellowstone_grpc_proto::tonic::Response<GetBlockHeightResponse>, Status> {
+        Err(Status::unimplemented("not supported by the mock server"))
+    }
+
+    async fn get_slot(
+        &self,
+        _request: yellowstone_grpc_proto::tonic::Request<GetSlotRequest>,
+    ) -> Result<yellowstone_grpc_proto::tonic::Response<GetSlotResponse>, Status> {
+        Err(Status::unimplemented("not supported by the mock server"))
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        _request: yellowstone_grpc_proto::tonic::Request<IsBlockhashValidRequest>,
+    ) -> Result<yellowstone_grpc_proto::tonic::Response<IsBlockhashValidResponse>, Status> {
+        Err(Status::unimplemented("not supported by the mock server"))
+    }
+
+    async fn get_version(
+        &self,
+        _request: yellowstone_grpc_proto::tonic::Request<GetVersionRequest>,
+    ) -> Result<yellowstone_grpc_proto::tonic::Response<GetVersionResponse>, Status> {
+        Err(Status::unimplemented("not supported by the mock server"))
+    }
+}
+
+/// Mock `ShredlinkService`; only `SubscribeTransactions` is implemented
+pub struct MockShredlink {
+    feed: MockFeed,
+}
+
+impl MockShredlink {
+    pub fn new(feed: MockFeed) -> Self {
+        Self { feed }
+    }
+}
+
+#[tonic::async_trait]
+impl ShredlinkService for MockShredlink {
+    type SubscribeEntriesStream = ReceiverStream<Result<Entry, tonic::Status>>;
+    type SubscribeTransactionsStream = ReceiverStream<Result<SubscribeTransactionsResponse, tonic::Status>>;
+
+    async fn subscribe_entries(
+        &self,
+        _request: tonic::Request<SubscribeEntriesRequest>,
+    ) -> Result<tonic::Response<Self::SubscribeEntriesStream>, tonic::Status> {
+        Err(tonic::Status::unimplemented("not supported by the mock server"))
+    }
+
+    async fn subscribe_transactions(
+        &self,
+        _request: tonic::Request<tonic::Streaming<SubscribeTransactionsRequest>>,
+    ) -> Result<tonic::Response<Self::SubscribeTransactionsStream>, tonic::Status> {
+        let stream = self.feed.stream(|transaction| {
+            signature_bytes(transaction)
+                .map(|signature| SubscribeTransactionsResponse {
+                    filters: vec!["transactions".to_string()],
+                    created_at: None,
+                    transaction: Some(ShredlinkUpdateTransaction {
+                        slot: transaction.slot,
+                        transaction: Some(Transaction {
+                            signatures: vec![signature],
+                            message: None,
+                        }),
+                    }),
+                })
+                .into_iter()
+                .collect()
+        });
+        Ok(tonic::Response::new(stream))
+    }
+}
+
+/// Serve one mock feed of the given kind on an already bound listener
+pub async fn serve_feed(kind: SourceKind, feed: MockFeed, listener: TcpListener) -> Result<()> {
+    let incoming = TcpListenerStream::new(listener);
+    
+    match kind {
+        SourceKind::Geyser => {
+            yellowstone_grpc_proto::tonic::transport::Server::builder()
+                .add_service(GeyserServer::new(MockGeyser::new(feed)))
+                .serve_with_incoming(incoming)
+                .await?;
+        }
+        SourceKind::Shredlink => {
+            tonic::transport::Server::builder()
+                .add_service(ShredlinkServiceServer::new(MockShredlink::new(feed)))
+                .serve_with_incoming(incoming)
+                .await?;
+        }
+    }
+    
+    Ok(())
+}
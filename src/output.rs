@@ -0,0 +1,26 @@
+//! Where progress and diagnostic lines go: stdout, unless a machine-readable report is printed there.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+
+/// Keep stdout for the rendered report; `status!` lines go to stderr from now on
+pub fn reserve_stdout() {
+    STDOUT_RESERVED.store(true, Ordering::Relaxed);
+}
+
+pub fn stdout_reserved() -> bool {
+    STDOUT_RESERVED.load(Ordering::Relaxed)
+}
+
+/// `println!` for everything that is not the report itself
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if $crate::output::stdout_reserved() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
@@ -0,0 +1,238 @@
+use dashmap::DashMap;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::time::sleep;
+
+use crate::source::{RunEpoch, SourceEvent, StreamSource};
+
+/// How a source is reconnected after its stream fails or ends
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    /// Give up after this many consecutive failed attempts (`None` retries forever)
+    pub max_retries: Option<u32>,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Random spread applied to each delay, as a fraction of it (0.2 = ±20%)
+    pub jitter: f64,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: None,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Exponential backoff with jitter for the given attempt (starting at 1)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let base = self.initial_backoff.saturating_mul(1 << exponent).min(self.max_backoff);
+        
+        if self.jitter <= 0.0 {
+            return base;
+        }
+        
+        let spread = rand::thread_rng().gen_range(-self.jitter..=self.jitter);
+        base.mul_f64((1.0 + spread).max(0.0))
+    }
+}
+
+/// A period during which a source had no working stream (ns since run epoch)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DowntimeWindow {
+    pub start_ns: u64,
+    pub end_ns: Option<u64>,
+}
+
+/// Connection history of one source
+#[derive(Debug, Clone, Default)]
+pub struct SourceHealth {
+    pub disconnects: u64,
+    pub downtime: Vec<DowntimeWindow>,
+}
+
+impl SourceHealth {
+    /// Open a downtime window; returns false if the source was already down
+    pub fn mark_down(&mut self, now_ns: u64) -> bool {
+        if self.is_down() {
+            return false;
+        }
+        
+        self.disconnects += 1;
+        self.downtime.push(DowntimeWindow { start_ns: now_ns, end_ns: None });
+        true
+    }
+
+    /// Close the open downtime window; returns false if the source was not down
+    pub fn mark_up(&mut self, now_ns: u64) -> bool {
+        match self.downtime.last_mut() {
+            Some(window) if window.end_ns.is_none() => {
+                window.end_ns = Some(now_ns);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_down(&self) -> bool {
+        self.downtime.last().is_some_and(|window| window.end_ns.is_none())
+    }
+
+    pub fn was_down_at(&self, timestamp_ns: u64) -> bool {
+        self.downtime.iter().any(|window| {
+            timestamp_ns >= window.start_ns && window.end_ns.is_none_or(|end| timestamp_ns <= end)
+        })
+    }
+
+    /// Total downtime, counting open windows up to `now_ns`
+    pub fn total_downtime(&self, now_ns: u64) -> Duration {
+        let nanos: u64 = self.downtime
+            .iter()
+            .map(|window| window.end_ns.unwrap_or(now_ns).saturating_sub(window.start_ns))
+            .sum();
+        Duration::from_nanos(nanos)
+    }
+}
+
+/// Run a source, reconnecting with backoff whenever its stream fails or ends.
+/// A downtime window stays open until the source delivers its next observation.
+pub async fn run_with_reconnect(
+    source: &mut dyn StreamSource,
+    tx: UnboundedSender<SourceEvent>,
+    policy: &ReconnectPolicy,
+    health: &DashMap<String, SourceHealth>,
+    epoch: RunEpoch,
+) {
+    let name = source.name().to_string();
+    let mut attempt = 0;
+    
+    loop {
+        let result = source.start(tx.clone(), epoch).await;
+        
+        if tx.is_closed() {
+            return;
+        }
+        
+        match result {
+            Ok(()) => eprintln!("🔌 {} stream ended", name),
+            Err(e) => eprintln!("❌ {} failed: {}", name, e),
+        }
+        
+        // Backoff restarts once the source has recovered and delivered data again
+        let recovered = health.entry(name.clone()).or_default().mark_down(epoch.timestamp_ns());
+        attempt = if recovered { 1 } else { attempt + 1 };
+        
+        if policy.max_retries.is_some_and(|max| attempt > max) {
+            eprintln!("🛑 {} giving up after {} reconnect attempts", name, attempt - 1);
+            return;
+        }
+        
+        let delay = policy.backoff(attempt);
+        eprintln!("🔄 Reconnecting {} in {:.1}s (attempt {})", name, delay.as_secs_f64(), attempt);
+        sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use futures::future::LocalBoxFuture;
+
+    fn policy(max_retries: Option<u32>) -> ReconnectPolicy {
+        ReconnectPolicy {
+            max_retries,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            jitter: 0.0,
+        }
+    }
+
+    /// Fails every time it is started; delivers data before failing on the `recovers_on`-th start
+    struct FlakySource<'a> {
+        health: &'a DashMap<String, SourceHealth>,
+        starts: u32,
+        recovers_on: Option<u32>,
+    }
+
+    impl StreamSource for FlakySource<'_> {
+        fn name(&self) -> &str {
+            "Flaky"
+        }
+
+        fn start(&mut self, _tx: UnboundedSender<SourceEvent>, epoch: RunEpoch) -> LocalBoxFuture<'_, Result<()>> {
+            self.starts += 1;
+            if self.recovers_on == Some(self.starts) {
+                self.health.get_mut("Flaky").unwrap().mark_up(epoch.timestamp_ns());
+            }
+            Box::pin(async { Err(anyhow::anyhow!("connection refused")) })
+        }
+    }
+
+    async fn starts_until_giving_up(recovers_on: Option<u32>) -> (u32, u64) {
+        let health = DashMap::new();
+        let mut source = FlakySource { health: &health, starts: 0, recovers_on };
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        run_with_reconnect(&mut source, tx, &policy(Some(2)), &health, RunEpoch::start()).await;
+        
+        let disconnects = health.get("Flaky").unwrap().disconnects;
+        (source.starts, disconnects)
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = policy(None);
+        let delays: Vec<u128> = (1..=5).map(|attempt| policy.backoff(attempt).as_millis()).collect();
+        
+        assert_eq!(delays, vec![1, 2, 4, 4, 4]);
+        assert_eq!(policy.backoff(1_000), Duration::from_millis(4));
+    }
+
+    #[test]
+    fn jitter_stays_within_its_spread() {
+        let policy = ReconnectPolicy { jitter: 0.2, ..ReconnectPolicy::default() };
+        
+        for _ in 0..1_000 {
+            let delay = policy.backoff(2);
+            assert!(delay >= Duration::from_millis(800) && delay <= Duration::from_millis(1_200), "{:?}", delay);
+        }
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        // The first start plus two reconnect attempts
+        assert_eq!(starts_until_giving_up(None).await, (3, 1));
+    }
+
+    #[tokio::test]
+    async fn attempts_restart_after_the_source_recovers() {
+        // Recovering on the third start allows two more reconnect attempts
+        assert_eq!(starts_until_giving_up(Some(3)).await, (5, 2));
+    }
+
+    #[test]
+    fn downtime_windows_are_counted_once() {
+        let mut health = SourceHealth::default();
+        
+        assert!(health.mark_down(100));
+        assert!(!health.mark_down(150));
+        assert!(health.mark_up(300));
+        assert!(!health.mark_up(350));
+        assert!(health.mark_down(500));
+        
+        assert_eq!(health.disconnects, 2);
+        assert!(health.is_down());
+        assert_eq!(health.total_downtime(700), Duration::from_nanos(400));
+        assert!(health.was_down_at(200));
+        assert!(!health.was_down_at(400));
+        assert!(health.was_down_at(600));
+        assert!(!health.was_down_at(50));
+    }
+}
@@ -0,0 +1,448 @@
+//! On-disk log of every message received from each source, and replay of such logs.
+//!
+//! The log starts with a magic header followed by records:
+//! - source: `0u8`, id (`u16`), kind (`u8`), name length (`u16`), UTF-8 name
+//! - message: `1u8`, source id (`u16`), receive time in ns since run epoch (`u64`), payload length (`u32`), protobuf payload
+//!
+//! Integers are little-endian. Payloads are the `SubscribeUpdate` (Geyser) or
+//! `SubscribeTransactionsResponse` (Shredlink) protobuf bytes exactly as read off the wire.
+//! Records are written by a dedicated thread, so disk writes never delay reading a stream.
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use futures::future::LocalBoxFuture;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::time::{sleep_until, Instant};
+
+use crate::config::SourceKind;
+use crate::geyser_client;
+use crate::shredlink_client;
+use crate::source::{RunEpoch, SourceEvent, StreamSource};
+
+const MAGIC: &[u8; 8] = b"SLREC01\n";
+const SOURCE_RECORD: u8 = 0;
+const MESSAGE_RECORD: u8 = 1;
+
+/// Work for the writer thread, handled in the order it was sent
+enum WriterCommand {
+    /// One encoded record
+    Write(Vec<u8>),
+    /// One received message, encoded by the writer thread
+    Message { id: u16, received_at: u64, payload: Bytes },
+    /// Flush and report the first write error, if any
+    Flush(Sender<io::Result<()>>),
+}
+
+/// Shared handle to a recording log and its writer thread
+#[derive(Clone)]
+pub struct Recorder {
+    commands: Sender<WriterCommand>,
+    next_id: Arc<Mutex<u16>>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("Failed to create recording {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(MAGIC)?;
+        
+        let (commands, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("recording-writer".to_string())
+            .spawn(move || write_records(writer, receiver))
+            .context("Failed to start the recording writer")?;
+        
+        Ok(Self {
+            commands,
+            next_id: Arc::new(Mutex::new(0)),
+        })
+    }
+
+    /// Register a source and get the handle its client records through
+    pub fn source(&self, name: &str, kind: SourceKind) -> Result<SourceRecorder> {
+        let id = {
+            let mut next_id = self.next_id.lock().unwrap();
+            let id = *next_id;
+            *next_id += 1;
+            id
+        };
+        
+        let name_len = u16::try_from(name.len()).context("Source name too long to record")?;
+        let mut record = Vec::with_capacity(6 + name.len());
+        record.push(SOURCE_RECORD);
+        record.extend_from_slice(&id.to_le_bytes());
+        record.push(kind_to_byte(kind));
+        record.extend_from_slice(&name_len.to_le_bytes());
+        record.extend_from_slice(name.as_bytes());
+        self.commands.send(WriterCommand::Write(record)).map_err(|_| writer_stopped())?;
+        
+        Ok(SourceRecorder { id, commands: self.commands.clone() })
+    }
+
+    /// Wait until everything sent so far is on disk
+    pub fn flush(&self) -> Result<()> {
+        let (reply, result) = mpsc::channel();
+        self.commands.send(WriterCommand::Flush(reply)).map_err(|_| writer_stopped())?;
+        result.recv().map_err(|_| writer_stopped())??;
+        Ok(())
+    }
+}
+
+/// Records the messages of one source
+pub struct SourceRecorder {
+    id: u16,
+    commands: Sender<WriterCommand>,
+}
+
+impl SourceRecorder {
+    /// Hand a received message to the writer thread without copying it; write errors surface in `Recorder::flush`
+    pub fn record(&self, received_at: u64, payload: Bytes) -> io::Result<()> {
+        if u32::try_from(payload.len()).is_err() {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "message too large to record"));
+        }
+        self.commands
+            .send(WriterCommand::Message { id: self.id, received_at, payload })
+            .map_err(|_| writer_stopped())
+    }
+}
+
+/// Writer thread: append records until every handle is dropped, keeping the first error for `flush`
+fn write_records(mut writer: BufWriter<File>, commands: Receiver<WriterCommand>) {
+    let mut error: Option<io::Error> = None;
+    
+    for command in commands {
+        match command {
+            WriterCommand::Write(record) => {
+                if error.is_none() {
+                    error = writer.write_all(&record).err();
+                }
+            }
+            WriterCommand::Message { id, received_at, payload } => {
+                if error.is_none() {
+                    error = write_message(&mut writer, id, received_at, &payload).err();
+                }
+            }
+            WriterCommand::Flush(reply) => {
+                let result = match &error {
+                    Some(e) => Err(io::Error::new(e.kind(), e.to_string())),
+                    None => writer.flush(),
+                };
+                let _ = reply.send(result);
+            }
+        }
+    }
+    let _ = writer.flush();
+}
+
+fn write_message(writer: &mut impl Write, id: u16, received_at: u64, payload: &[u8]) -> io::Result<()> {
+    writer.write_all(&[MESSAGE_RECORD])?;
+    writer.write_all(&id.to_le_bytes())?;
+    writer.write_all(&received_at.to_le_bytes())?;
+    writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+    writer.write_all(payload)
+}
+
+fn writer_stopped() -> io::Error {
+    io::Error::new(ErrorKind::BrokenPipe, "recording writer stopped")
+}
+
+/// One recorded message
+#[derive(Debug, Clone)]
+pub struct RecordedMessage {
+    pub received_at: u64,
+    pub payload: Vec<u8>,
+}
+
+/// Every message of one recorded source, in receive order
+#[derive(Debug, Clone)]
+pub struct RecordedSource {
+    pub name: String,
+    pub kind: SourceKind,
+    pub messages: Vec<RecordedMessage>,
+}
+
+/// A recording loaded into memory
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub sources: Vec<RecordedSource>,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open recording {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+        
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).with_context(|| format!("{} is not a recording", path.display()))?;
+        if &magic != MAGIC {
+            return Err(anyhow::anyhow!("{} is not a recording", path.display()));
+        }
+        
+        let mut sources: Vec<RecordedSource> = Vec::new();
+        loop {
+            let mut tag = [0u8; 1];
+            match reader.read_exact(&mut tag) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+            
+            // A run that was killed may leave a truncated last record behind
+            let result = match tag[0] {
+                SOURCE_RECORD => read_source(&mut reader).and_then(|(id, source)| {
+                    if id as usize != sources.len() {
+                        return Err(anyhow::anyhow!("Unexpected source id {}", id));
+                    }
+                    sources.push(source);
+                    Ok(())
+                }),
+                MESSAGE_RECORD => read_message(&mut reader).and_then(|(id, message)| {
+                    let source = sources.get_mut(id as usize)
+                        .ok_or_else(|| anyhow::anyhow!("Message for unknown source id {}", id))?;
+                    source.messages.push(message);
+                    Ok(())
+                }),
+                other => Err(anyhow::anyhow!("Unknown record type {}", other)),
+            };
+            
+            match result {
+                Ok(()) => {}
+                Err(e) if is_truncated(&e) => {
+                    eprintln!("⚠️  {} ends with a truncated record, ignoring it", path.display());
+                    break;
+                }
+                Err(e) => return Err(e.context(format!("Corrupt recording {}", path.display()))),
+            }
+        }
+        
+        Ok(Self { sources })
+    }
+
+    /// Time between the run epoch and the last recorded message
+    pub fn duration_ns(&self) -> u64 {
+        self.sources
+            .iter()
+            .filter_map(|source| source.messages.last())
+            .map(|message| message.received_at)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn message_count(&self) -> usize {
+        self.sources.iter().map(|source| source.messages.len()).sum()
+    }
+
+    /// One replaying source per recorded source
+    pub fn into_sources(self) -> Vec<Box<dyn StreamSource>> {
+        self.sources
+            .into_iter()
+            .map(|source| Box::new(ReplaySource::new(source)) as Box<dyn StreamSource>)
+            .collect()
+    }
+}
+
+fn read_source(reader: &mut impl Read) -> Result<(u16, RecordedSource)> {
+    let id = u16::from_le_bytes(read_array(reader)?);
+    let [kind] = read_array(reader)?;
+    let name_len = u16::from_le_bytes(read_array(reader)?);
+    let mut name = vec![0u8; name_len as usize];
+    reader.read_exact(&mut name)?;
+    
+    let kind = match kind {
+        0 => SourceKind::Geyser,
+        1 => SourceKind::Shredlink,
+        other => return Err(anyhow::anyhow!("Unknown source kind {}", other)),
+    };
+    
+    let source = RecordedSource {
+        name: String::from_utf8(name).context("Source name is not UTF-8")?,
+        kind,
+        messages: Vec::new(),
+    };
+    Ok((id, source))
+}
+
+fn read_message(reader: &mut impl Read) -> Result<(u16, RecordedMessage)> {
+    let id = u16::from_le_bytes(read_array(reader)?);
+    let received_at = u64::from_le_bytes(read_array(reader)?);
+    let payload_len = u32::from_le_bytes(read_array(reader)?);
+    let mut payload = vec![0u8; payload_len as usize];
+    reader.read_exact(&mut payload)?;
+    
+    Ok((id, RecordedMessage { received_at, payload }))
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn is_truncated(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == ErrorKind::UnexpectedEof)
+}
+
+fn kind_to_byte(kind: SourceKind) -> u8 {
+    match kind {
+        SourceKind::Geyser => 0,
+        SourceKind::Shredlink => 1,
+    }
+}
+
+/// Feeds a recorded source back through the pipeline at its original receive times
+pub struct ReplaySource {
+    source: RecordedSource,
+}
+
+impl ReplaySource {
+    pub fn new(source: RecordedSource) -> Self {
+        Self { source }
+    }
+
+    async fn replay(&mut self, tx: UnboundedSender<SourceEvent>, epoch: RunEpoch) -> Result<()> {
+        let epoch = Instant::from_std(epoch.instant());
+        
+        for message in &self.source.messages {
+            sleep_until(epoch + Duration::from_nanos(message.received_at)).await;
+            
+            let event = match self.source.kind {
+                SourceKind::Geyser => geyser_client::decode_event(&self.source.name, &message.payload, message.received_at)?,
+                SourceKind::Shredlink => shredlink_client::decode_event(&self.source.name, &message.payload, message.received_at)?,
+            };
+            
+            if let Some(event) = event {
+                if tx.send(event).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+        
+        // Stay connected so the end of the log is not treated as a disconnect
+        tx.closed().await;
+        Ok(())
+    }
+}
+
+impl StreamSource for ReplaySource {
+    fn name(&self) -> &str {
+        &self.source.name
+    }
+
+    fn start(&mut self, tx: UnboundedSender<SourceEvent>, epoch: RunEpoch) -> LocalBoxFuture<'_, Result<()>> {
+        Box::pin(self.replay(tx, epoch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("shredlink-{}-{}.slrec", name, std::process::id()))
+    }
+
+    fn write_recording(path: &Path) {
+        let recorder = Recorder::create(path).unwrap();
+        let geyser = recorder.source("Geyser", SourceKind::Geyser).unwrap();
+        let shredlink = recorder.source("Shredlink", SourceKind::Shredlink).unwrap();
+        
+        geyser.record(10, Bytes::from_static(b"first")).unwrap();
+        shredlink.record(12, Bytes::from_static(&[0, 1, 2, 255])).unwrap();
+        geyser.record(30, Bytes::new()).unwrap();
+        shredlink.record(41, Bytes::from_static(b"last")).unwrap();
+        recorder.flush().unwrap();
+    }
+
+    #[test]
+    fn recorded_messages_load_back_unchanged() {
+        let path = temp_path("round-trip");
+        write_recording(&path);
+        let recording = Recording::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        
+        assert_eq!(recording.sources.len(), 2);
+        let geyser = &recording.sources[0];
+        assert_eq!((geyser.name.as_str(), geyser.kind), ("Geyser", SourceKind::Geyser));
+        let messages: Vec<(u64, &[u8])> = geyser.messages.iter().map(|message| (message.received_at, &message.payload[..])).collect();
+        assert_eq!(messages, vec![(10, &b"first"[..]), (30, &b""[..])]);
+        
+        let shredlink = &recording.sources[1];
+        assert_eq!((shredlink.name.as_str(), shredlink.kind), ("Shredlink", SourceKind::Shredlink));
+        let messages: Vec<(u64, &[u8])> = shredlink.messages.iter().map(|message| (message.received_at, &message.payload[..])).collect();
+        assert_eq!(messages, vec![(12, &[0, 1, 2, 255][..]), (41, &b"last"[..])]);
+        
+        assert_eq!(recording.message_count(), 4);
+        assert_eq!(recording.duration_ns(), 41);
+    }
+
+    #[test]
+    fn truncated_last_record_is_dropped() {
+        let path = temp_path("truncated");
+        write_recording(&path);
+        let len = fs::metadata(&path).unwrap().len();
+        OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 2).unwrap();
+        let recording = Recording::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        
+        assert_eq!(recording.message_count(), 3);
+        assert_eq!(recording.sources[1].messages.len(), 1);
+        assert_eq!(recording.duration_ns(), 30);
+    }
+
+    #[test]
+    fn other_files_are_rejected() {
+        let path = temp_path("not-a-recording");
+        fs::write(&path, b"{\"not\": \"a recording\"}").unwrap();
+        let result = Recording::load(&path);
+        fs::remove_file(&path).unwrap();
+        
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn replay_is_timed_from_the_epoch_it_is_given() {
+        use yellowstone_grpc_proto::geyser::subscribe_update::UpdateOneof;
+        use yellowstone_grpc_proto::geyser::{SlotStatus, SubscribeUpdate, SubscribeUpdateSlot};
+        use yellowstone_grpc_proto::prost::Message;
+        
+        let update = SubscribeUpdate {
+            filters: vec!["slots".to_string()],
+            created_at: None,
+            update_oneof: Some(UpdateOneof::Slot(SubscribeUpdateSlot {
+                slot: 7,
+                parent: None,
+                status: SlotStatus::SlotProcessed as i32,
+                dead_error: None,
+            })),
+        };
+        let source = RecordedSource {
+            name: "Geyser".to_string(),
+            kind: SourceKind::Geyser,
+            messages: vec![RecordedMessage { received_at: 200_000_000, payload: update.encode_to_vec() }],
+        };
+        
+        // An earlier epoch must not pull the replay forward
+        let _earlier = RunEpoch::start();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let epoch = RunEpoch::start();
+        
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut replay = ReplaySource::new(source);
+        let event = tokio::select! {
+            result = replay.start(tx, epoch) => panic!("Replay ended early: {:?}", result),
+            event = rx.recv() => event.unwrap(),
+        };
+        
+        assert_eq!(event.received_at(), 200_000_000);
+        assert!(epoch.instant().elapsed() >= Duration::from_millis(200));
+    }
+}
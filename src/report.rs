@@ -0,0 +1,474 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::benchmark::{format_p_value, BenchmarkReport, GeyserStats, TransactionTimestamp};
+use crate::leaders::{GroupStats, TOP_LEADERS};
+
+/// Output format for the final benchmark report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    /// Colored human-readable summary
+    Table,
+    /// Full report as pretty-printed JSON
+    Json,
+    /// One row per source
+    Csv,
+    /// Markdown table, one row per source
+    Markdown,
+}
+
+/// Output format for the raw per-transaction observation dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DumpFormat {
+    /// One JSON object per line
+    Jsonl,
+    /// One row per transaction, one timestamp column per source
+    Csv,
+}
+
+/// Render a report in a machine-readable format
+pub fn render(report: &BenchmarkReport, format: ReportFormat) -> Result<String> {
+    match format {
+        ReportFormat::Json => Ok(serde_json::to_string_pretty(report)? + "\n"),
+        ReportFormat::Csv => Ok(render_csv(report)),
+        ReportFormat::Markdown => Ok(render_markdown(report)),
+        ReportFormat::Table => Err(anyhow::anyhow!("Table reports are printed directly, not rendered")),
+    }
+}
+
+/// Render one daemon window followed by the cumulative report since measurement started.
+/// JSON is one line per window; CSV rows carry a scope column and the header only when `header` is set.
+pub fn render_window(window: &BenchmarkReport, cumulative: &BenchmarkReport, format: ReportFormat, header: bool) -> Result<String> {
+    match format {
+        ReportFormat::Json => Ok(serde_json::to_string(&json!({ "window": window, "cumulative": cumulative }))? + "\n"),
+        ReportFormat::Csv => {
+            let mut out = String::new();
+            for (scope, report) in [("window", window), ("cumulative", cumulative)] {
+                let rendered = render_csv(report);
+                let mut lines = rendered.lines();
+                let columns = lines.next().unwrap_or_default();
+                if header && scope == "window" {
+                    let _ = writeln!(out, "scope,window_start,window_end,{}", columns);
+                }
+                for line in lines {
+                    let _ = writeln!(out, "{},{},{},{}", scope, report.start_time.to_rfc3339(), report.end_time.to_rfc3339(), line);
+                }
+            }
+            Ok(out)
+        }
+        ReportFormat::Markdown => Ok(format!(
+            "# Window {} – {}\n\n{}\n# Cumulative since {}\n\n{}\n",
+            window.start_time.to_rfc3339(),
+            window.end_time.to_rfc3339(),
+            render_markdown(window),
+            cumulative.start_time.to_rfc3339(),
+            render_markdown(cumulative),
+        )),
+        ReportFormat::Table => Err(anyhow::anyhow!("Table reports are printed directly, not rendered")),
+    }
+}
+
+/// Source rows ordered by name so output is stable between runs
+fn sorted_results(report: &BenchmarkReport) -> Vec<(&String, &GeyserStats)> {
+    let mut results: Vec<_> = report.source_results.iter().collect();
+    results.sort_by(|a, b| a.0.cmp(b.0));
+    results
+}
+
+fn render_csv(report: &BenchmarkReport) -> String {
+    let mut out = String::from(
+        "source,baseline,matched_transactions,source_only_count,baseline_only_count,\
+         average_latency_us,median_latency_us,p95_latency_us,p99_latency_us,\
+         min_latency_us,max_latency_us,baseline_wins_percentage,disconnects,downtime_seconds,\
+         baseline_wins,ties,baseline_losses,tie_tolerance_ms,\
+         median_ci_low_us,median_ci_high_us,p95_ci_low_us,p95_ci_high_us,\
+         baseline_wins_ci_low_percentage,baseline_wins_ci_high_percentage,sign_test_p_value,wilcoxon_p_value,\
+         match_window_seconds,missed,baseline_missed,\
+         first_in_slot_percentage,slot_lag_median_us,slot_lag_p95_us,slot_disagreements\n",
+    );
+    
+    for (source_name, stats) in sorted_results(report) {
+        let (disconnects, downtime_seconds) = connection_columns(report, source_name);
+        let slot_stats = report.slots.sources.iter().find(|slot| slot.source == *source_name);
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{},{:.3},{},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:e},{:e},{:.0},{},{},{:.3},{:.3},{:.3},{}",
+            csv_field(source_name),
+            csv_field(&report.baseline),
+            stats.matched_transactions,
+            stats.source_only_count,
+            report.baseline_only_count,
+            stats.average_latency_us,
+            stats.median_latency_us,
+            stats.p95_latency_us,
+            stats.p99_latency_us,
+            stats.min_latency_us,
+            stats.max_latency_us,
+            stats.baseline_wins_percentage,
+            disconnects,
+            downtime_seconds,
+            stats.baseline_wins,
+            stats.ties,
+            stats.baseline_losses,
+            report.tie_tolerance_ms,
+            stats.median_latency_ci_ms.low * 1000.0,
+            stats.median_latency_ci_ms.high * 1000.0,
+            stats.p95_latency_ci_ms.low * 1000.0,
+            stats.p95_latency_ci_ms.high * 1000.0,
+            stats.baseline_wins_ci_percentage.low,
+            stats.baseline_wins_ci_percentage.high,
+            stats.sign_test_p_value,
+            stats.wilcoxon_p_value,
+            report.match_window_seconds,
+            missed_count(report, source_name),
+            missed_count(report, &report.baseline),
+            slot_stats.map_or(0.0, |slot| slot.first_in_slot_percentage),
+            slot_stats.map_or(0.0, |slot| slot.median_lag_ms * 1000.0),
+            slot_stats.map_or(0.0, |slot| slot.p95_lag_ms * 1000.0),
+            report.slots.disagreements,
+        );
+    }
+    
+    out
+}
+
+fn render_markdown(report: &BenchmarkReport) -> String {
+    let mut out = String::new();
+    
+    let _ = writeln!(out, "## Benchmark results vs {}", report.baseline);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "- Start: {}", report.start_time.to_rfc3339());
+    let _ = writeln!(out, "- Duration: {:.1}s{}", report.duration_seconds, if report.interrupted { " (interrupted)" } else { "" });
+    if report.warmup_seconds > 0.0 {
+        let _ = writeln!(out, "- Warm-up (excluded): {:.1}s", report.warmup_seconds);
+    }
+    let _ = writeln!(out, "- Total transactions: {}", report.total_transactions);
+    let _ = writeln!(out, "- Only seen by {}: {}", report.baseline, report.baseline_only_count);
+    if report.possibly_failed_count > 0 {
+        let _ = writeln!(out, "- Possibly failed (only delivered by sources that keep failed transactions): {}", report.possibly_failed_count);
+    }
+    let _ = writeln!(out, "- Missed by {} within the {:.0}s match window: {}", report.baseline, report.match_window_seconds, missed_count(report, &report.baseline));
+    let _ = writeln!(out, "- Tie tolerance: ±{:.3}ms", report.tie_tolerance_ms);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Source | Matched | Source only | Avg (ms) | Median (ms) | P95 (ms) | P99 (ms) | Min (ms) | Max (ms) | {} wins (%) | Wins / ties / losses | Median 95% CI (ms) | Win rate 95% CI (%) | Wilcoxon p | Missed |", report.baseline);
+    let _ = writeln!(out, "|---|---:|---:|---:|---:|---:|---:|---:|---:|---:|---:|---:|---:|---:|---:|");
+    
+    for (source_name, stats) in sorted_results(report) {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {:.3} | {:.3} | {:.3} | {:.3} | {:.3} | {:.3} | {:.1} | {} / {} / {} | {:.3} – {:.3} | {:.1} – {:.1} | {} | {} |",
+            source_name.replace('|', "\\|"),
+            stats.matched_transactions,
+            stats.source_only_count,
+            stats.average_latency_ms,
+            stats.median_latency_ms,
+            stats.p95_latency_ms,
+            stats.p99_latency_ms,
+            stats.min_latency_ms,
+            stats.max_latency_ms,
+            stats.baseline_wins_percentage,
+            stats.baseline_wins,
+            stats.ties,
+            stats.baseline_losses,
+            stats.median_latency_ci_ms.low,
+            stats.median_latency_ci_ms.high,
+            stats.baseline_wins_ci_percentage.low,
+            stats.baseline_wins_ci_percentage.high,
+            format_p_value(stats.wilcoxon_p_value),
+            missed_count(report, source_name),
+        );
+    }
+    
+    render_markdown_leaderboard(&mut out, report);
+    render_markdown_slots(&mut out, report);
+    render_markdown_slot_updates(&mut out, report);
+    render_markdown_leaders(&mut out, report);
+    render_markdown_matrix(&mut out, report);
+    out
+}
+
+fn render_markdown_leaderboard(out: &mut String, report: &BenchmarkReport) {
+    let leaderboard = &report.leaderboard;
+    
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### First-arrival leaderboard");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{} transactions seen by at least {} sources.", leaderboard.transactions, leaderboard.quorum);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Rank | Source | First (%) | First | Seen | Median lag (ms) | P95 lag (ms) | P99 lag (ms) |");
+    let _ = writeln!(out, "|---:|---|---:|---:|---:|---:|---:|---:|");
+    
+    for (rank, entry) in leaderboard.sources.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {:.1} | {} | {} | {:.3} | {:.3} | {:.3} |",
+            rank + 1,
+            entry.source.replace('|', "\\|"),
+            entry.first_arrival_percentage,
+            entry.first_arrivals,
+            entry.seen,
+            entry.median_lag_ms,
+            entry.p95_lag_ms,
+            entry.p99_lag_ms,
+        );
+    }
+}
+
+fn render_markdown_slots(out: &mut String, report: &BenchmarkReport) {
+    let slots = &report.slots;
+    if slots.slots == 0 {
+        return;
+    }
+    
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### Per-slot first arrivals");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{} slots; lag is each source's first transaction of a slot behind the earliest source's.", slots.slots);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Source | First in slot (%) | First in slot | Slots seen | Median lag (ms) | P95 lag (ms) | P99 lag (ms) | Max lag (ms) |");
+    let _ = writeln!(out, "|---|---:|---:|---:|---:|---:|---:|---:|");
+    for source in &slots.sources {
+        let _ = writeln!(
+            out,
+            "| {} | {:.1} | {} | {} | {:.3} | {:.3} | {:.3} | {:.3} |",
+            source.source.replace('|', "\\|"),
+            source.first_in_slot_percentage,
+            source.first_in_slot,
+            source.slots_seen,
+            source.median_lag_ms,
+            source.p95_lag_ms,
+            source.p99_lag_ms,
+            source.max_lag_ms,
+        );
+    }
+    
+    if slots.disagreements > 0 {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{} transactions were reported in different slots by different sources, e.g.:", slots.disagreements);
+        let _ = writeln!(out);
+        for example in &slots.disagreement_examples {
+            let placements: Vec<String> = example.slots.iter().map(|(source, slot)| format!("{} {}", source, slot)).collect();
+            let _ = writeln!(out, "- `{}`: {}", example.signature, placements.join(", "));
+        }
+    }
+}
+
+fn render_markdown_slot_updates(out: &mut String, report: &BenchmarkReport) {
+    if report.slot_updates.is_empty() {
+        return;
+    }
+    
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### Slot updates");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Lag is each source's announcement of a slot status behind the earliest source's.");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Status | Source | First (%) | First | Slots announced | Median lag (ms) | P95 lag (ms) | P99 lag (ms) | Max lag (ms) |");
+    let _ = writeln!(out, "|---|---|---:|---:|---:|---:|---:|---:|---:|");
+    for status in &report.slot_updates {
+        for source in &status.sources {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {:.1} | {} | {} | {:.3} | {:.3} | {:.3} | {:.3} |",
+                status.status.as_str(),
+                source.source.replace('|', "\\|"),
+                source.first_in_slot_percentage,
+                source.first_in_slot,
+                source.slots_seen,
+                source.median_lag_ms,
+                source.p95_lag_ms,
+                source.p99_lag_ms,
+                source.max_lag_ms,
+            );
+        }
+    }
+}
+
+fn render_markdown_leaders(out: &mut String, report: &BenchmarkReport) {
+    let Some(leaders) = &report.leaders else {
+        return;
+    };
+    
+    if !leaders.by_region.is_empty() {
+        render_markdown_groups(out, "By leader region", "Region", &leaders.by_region);
+    }
+    let shown = TOP_LEADERS.min(leaders.by_leader.len());
+    render_markdown_groups(out, &format!("By leader (top {} of {})", shown, leaders.by_leader.len()), "Leader", &leaders.by_leader[..shown]);
+    if leaders.unknown_leader_transactions > 0 {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{} transactions had no leader in the schedule.", leaders.unknown_leader_transactions);
+    }
+}
+
+/// One row per group; each source cell is first-arrival % / median lag / p95 lag behind the earliest arrival
+fn render_markdown_groups(out: &mut String, title: &str, label: &str, groups: &[GroupStats]) {
+    let Some(columns) = groups.first().map(|group| &group.sources) else {
+        return;
+    };
+    
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### {}", title);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Each cell: first arrivals (%) / median lag (ms) / P95 lag (ms) behind the earliest source.");
+    let _ = writeln!(out);
+    
+    let mut header = format!("| {} | Transactions |", label);
+    let mut separator = String::from("|---|---:|");
+    for column in columns {
+        let _ = write!(header, " {} |", column.source.replace('|', "\\|"));
+        separator.push_str("---:|");
+    }
+    let _ = writeln!(out, "{}", header);
+    let _ = writeln!(out, "{}", separator);
+    
+    for group in groups {
+        let mut row = format!("| {} | {} |", group.group.replace('|', "\\|"), group.transactions);
+        for stats in &group.sources {
+            if stats.seen == 0 {
+                row.push_str(" - |");
+            } else {
+                let _ = write!(row, " {:.1} / {:.3} / {:.3} |", stats.first_arrival_percentage, stats.median_lag_ms, stats.p95_lag_ms);
+            }
+        }
+        let _ = writeln!(out, "{}", row);
+    }
+}
+
+/// N×N matrix; each cell compares the row source with the column source
+fn render_markdown_matrix(out: &mut String, report: &BenchmarkReport) {
+    let mut names: Vec<&String> = report.pairwise.iter().map(|pair| &pair.row).collect();
+    names.dedup();
+    if names.is_empty() {
+        return;
+    }
+    
+    let escaped: Vec<String> = names.iter().map(|name| name.replace('|', "\\|")).collect();
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### Pairwise matrix");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Each cell: median / p95 difference in ms (positive means the row source is faster), row win rate, matched transactions, Wilcoxon p-value.");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Row \\ Column | {} |", escaped.join(" | "));
+    let _ = writeln!(out, "|---|{}", "---:|".repeat(names.len()));
+    
+    for (row, row_label) in names.iter().zip(&escaped) {
+        let cells: Vec<String> = names
+            .iter()
+            .map(|column| {
+                report.pairwise
+                    .iter()
+                    .find(|pair| pair.row == **row && pair.column == **column)
+                    .map(|pair| format!(
+                        "{:+.3} / {:+.3}, {:.1}%, {}, p={}",
+                        pair.median_diff_ms, pair.p95_diff_ms, pair.row_wins_percentage, pair.matched_transactions, format_p_value(pair.wilcoxon_p_value)
+                    ))
+                    .unwrap_or_else(|| "—".to_string())
+            })
+            .collect();
+        let _ = writeln!(out, "| {} | {} |", row_label, cells.join(" | "));
+    }
+}
+
+fn connection_columns(report: &BenchmarkReport, source_name: &str) -> (u64, f64) {
+    report
+        .connection_stats
+        .get(source_name)
+        .map(|stats| (stats.disconnects, stats.downtime_seconds))
+        .unwrap_or_default()
+}
+
+fn missed_count(report: &BenchmarkReport, source_name: &str) -> usize {
+    report.missed.get(source_name).copied().unwrap_or(0)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Streams one row per finalized transaction with every source's arrival timestamp (ns since run epoch)
+pub struct ObservationWriter {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    format: DumpFormat,
+    source_names: Vec<String>,
+    rows: usize,
+}
+
+impl ObservationWriter {
+    pub fn create(path: &Path, format: DumpFormat, source_names: Vec<String>) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("Failed to create observation dump {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        
+        if format == DumpFormat::Csv {
+            let header: Vec<String> = source_names
+                .iter()
+                .map(|name| csv_field(&format!("{}_ns", name)))
+                .chain(source_names.iter().map(|name| csv_field(&format!("{}_slot", name))))
+                .collect();
+            writeln!(writer, "signature,slot,{}", header.join(","))?;
+        }
+        
+        Ok(Self { writer, path: path.to_path_buf(), format, source_names, rows: 0 })
+    }
+    
+    pub fn write(&mut self, signature: &str, entry: &TransactionTimestamp) -> Result<()> {
+        match self.format {
+            DumpFormat::Jsonl => {
+                let timestamps: Map<String, Value> = self.source_names
+                    .iter()
+                    .map(|name| (name.clone(), json!(entry.timestamps.get(name))))
+                    .collect();
+                let slots: Map<String, Value> = self.source_names
+                    .iter()
+                    .map(|name| (name.clone(), json!(entry.slots.get(name))))
+                    .collect();
+                let row = json!({
+                    "signature": signature,
+                    "slot": entry.slot,
+                    "timestamps_ns": timestamps,
+                    "slots": slots,
+                });
+                writeln!(self.writer, "{}", row)?;
+            }
+            DumpFormat::Csv => {
+                let columns: Vec<String> = self.source_names
+                    .iter()
+                    .map(|name| entry.timestamps.get(name).map(|ts| ts.to_string()).unwrap_or_default())
+                    .chain(self.source_names.iter().map(|name| entry.slots.get(name).map(|slot| slot.to_string()).unwrap_or_default()))
+                    .collect();
+                let slot = entry.slot.map(|slot| slot.to_string()).unwrap_or_default();
+                writeln!(self.writer, "{},{},{}", signature, slot, columns.join(","))?;
+            }
+        }
+        
+        self.rows += 1;
+        Ok(())
+    }
+    
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+    
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    /// Flush the file and return the number of rows written
+    pub fn finish(mut self) -> Result<usize> {
+        self.writer.flush()?;
+        Ok(self.rows)
+    }
+}
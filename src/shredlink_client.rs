@@ -1,72 +1,147 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use futures::future::LocalBoxFuture;
 use std::collections::HashMap;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
-use shredlink_proto::shredlink::{SubscribeTransactionsRequest, SubscribeRequestFilterTransactions};
-use shredlink_proto::shredlink::shredlink_service_client::ShredlinkServiceClient;
+use tonic::client::Grpc;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::{AsciiMetadataKey, AsciiMetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tonic::Request;
+use prost::Message;
+use shredlink_proto::shredlink::{SubscribeTransactionsRequest, SubscribeTransactionsResponse, SubscribeRequestFilterTransactions};
 
-// These should be generated from your actual Shredlink protobuf files
-// Based on your TypeScript usage
+use crate::config::{SourceConfig, TransactionFilter, VOTE_PROGRAM_ID};
+use crate::grpc::{self, RawCodec, TlsFiles};
+use crate::recording::SourceRecorder;
+use crate::source::{Observation, RunEpoch, SourceEvent, StreamSource};
+use crate::status;
 
-#[derive(Debug, Clone)]
-pub struct ShredlinkTransaction {
-    pub signatures: Vec<Vec<u8>>,
-}
+const SUBSCRIBE_PATH: &str = "/shredlink.ShredlinkService/SubscribeTransactions";
+
+type MetadataInterceptor = grpc::MetadataInterceptor<AsciiMetadataKey, AsciiMetadataValue>;
 
 pub struct ShredlinkClient {
-    pub url: String,
+    config: SourceConfig,
+    filter: TransactionFilter,
+    vote_program: Vec<u8>,
+    recorder: Option<SourceRecorder>,
 }
 
 impl ShredlinkClient {
-    pub fn new(url: String) -> Self {
-        Self { url }
+    pub fn new(config: SourceConfig, recorder: Option<SourceRecorder>) -> Self {
+        let filter = config.filter();
+        let vote_program = bs58::decode(VOTE_PROGRAM_ID).into_vec().expect("valid vote program id");
+        
+        Self { config, filter, vote_program, recorder }
+    }
+    
+    /// Shredlink has no server-side vote filter, so apply it to the decoded message
+    fn passes_vote_filter(&self, response: &SubscribeTransactionsResponse) -> bool {
+        let Some(keep_votes) = self.filter.vote else {
+            return true;
+        };
+        
+        let is_vote = response.transaction
+            .as_ref()
+            .and_then(|update| update.transaction.as_ref())
+            .and_then(|transaction| transaction.message.as_ref())
+            .is_some_and(|message| message.account_keys.contains(&self.vote_program));
+        is_vote == keep_votes
     }
 
-    pub async fn start(&mut self, tx_sender: mpsc::UnboundedSender<ShredlinkTransaction>) -> Result<()> {
-        println!("🔄 Connecting to Shredlink at: {}", self.url);
+    /// TLS settings; the native and webpki roots are always trusted, plus the custom CA if set
+    fn tls_config(&self) -> Result<ClientTlsConfig> {
+        let files = TlsFiles::load(&self.config.tls)?;
+        
+        // tonic 0.10 always trusts the native and webpki roots; a custom CA is added on top
+        let mut tls_config = ClientTlsConfig::new();
+        if let Some(pem) = files.ca_cert {
+            tls_config = tls_config.ca_certificate(Certificate::from_pem(pem));
+        }
+        if let Some((cert, key)) = files.identity {
+            tls_config = tls_config.identity(Identity::from_pem(cert, key));
+        }
+        if let Some(domain_name) = files.domain_name {
+            tls_config = tls_config.domain_name(domain_name);
+        }
+        
+        Ok(tls_config)
+    }
+    
+    async fn create_client(&self) -> Result<Grpc<InterceptedService<Channel, MetadataInterceptor>>> {
+        let mut endpoint = Endpoint::from_shared(self.config.url.clone())?;
+        
+        // Configure TLS for HTTPS endpoints or explicit TLS options
+        if self.config.uses_tls() {
+            endpoint = endpoint.tls_config(self.tls_config()?)?;
+        }
+        
+        let channel = endpoint.connect().await?;
         
-        let channel = tonic::transport::Endpoint::from_shared(self.url.clone())?
-            .connect().await?;
-        let mut client = ShredlinkServiceClient::new(channel);
+        // Add token and header authentication if provided
+        let interceptor = MetadataInterceptor::new(&self.config)?;
+        
+        Ok(Grpc::new(InterceptedService::new(channel, interceptor)))
+    }
+
+    async fn subscribe(&mut self, tx_sender: mpsc::UnboundedSender<SourceEvent>, epoch: RunEpoch) -> Result<()> {
+        status!("🔄 Connecting to {} at: {}", self.config.name, self.config.url);
+        
+        let mut client = self.create_client().await?;
+        client.ready().await
+            .map_err(|e| anyhow::anyhow!("Shredlink service not ready: {}", e))?;
         
         // Create request/response channels for streaming
         let (subscribe_tx, subscribe_rx) = tokio::sync::mpsc::unbounded_channel();
         
-        // Start subscription
-        let response = client.subscribe_transactions(UnboundedReceiverStream::new(subscribe_rx)).await?;
+        // Start subscription; messages arrive undecoded, so they can be recorded exactly as received
+        let request = Request::new(UnboundedReceiverStream::new(subscribe_rx));
+        let response = client.streaming(request, PathAndQuery::from_static(SUBSCRIBE_PATH), RawCodec).await?;
         let mut stream = response.into_inner();
         
         // Send the subscribe request
-        let request = self.create_request();
-        let _ = subscribe_tx.send(request);
+        let _ = subscribe_tx.send(Bytes::from(self.create_request().encode_to_vec()));
         
-        println!("✅ Shredlink subscribed successfully");
+        status!("✅ {} subscribed successfully", self.config.name);
         
         // Handle incoming transaction stream
-        while let Some(message) = stream.message().await? {
-            if let Some(transaction_update) = message.transaction {
-                if let Some(transaction) = transaction_update.transaction {
-                    let shredlink_tx = ShredlinkTransaction {
-                        signatures: transaction.signatures,
-                    };
-                    
-                    let _ = tx_sender.send(shredlink_tx);
+        while let Some(payload) = stream.message().await? {
+            let received_at = epoch.timestamp_ns();
+            let message = SubscribeTransactionsResponse::decode(&payload[..])
+                .context("Invalid Shredlink message")?;
+            
+            // Replay cannot apply the vote filter, so only what passed it is recorded
+            if !self.passes_vote_filter(&message) {
+                continue;
+            }
+            
+            if let Some(recorder) = &self.recorder {
+                if let Err(e) = recorder.record(received_at, payload) {
+                    eprintln!("❌ Failed to record {} message: {}", self.config.name, e);
                 }
             }
+            
+            if let Some(observation) = observation_from_response(&self.config.name, message, received_at) {
+                let _ = tx_sender.send(SourceEvent::Transaction(observation));
+            }
         }
         
-        eprintln!("🔌 Shredlink stream ended");
         Ok(())
     }
 
     fn create_request(&self) -> SubscribeTransactionsRequest {
+        let filter = self.filter.clone();
+        
         let mut transactions = HashMap::new();
         transactions.insert(
-            "pumpfun".to_string(),
+            "transactions".to_string(),
             SubscribeRequestFilterTransactions {
-                account_include: vec![],
-                account_exclude: vec![],
-                account_required: vec!["6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P".to_string()],
+                account_include: filter.account_include,
+                account_exclude: filter.account_exclude,
+                account_required: filter.account_required,
             }
         );
 
@@ -75,4 +150,33 @@ impl ShredlinkClient {
             request_type: None,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Decode a recorded `SubscribeTransactionsResponse` into an event
+pub fn decode_event(source: &str, payload: &[u8], received_at: u64) -> Result<Option<SourceEvent>> {
+    let response = SubscribeTransactionsResponse::decode(payload)?;
+    Ok(observation_from_response(source, response, received_at).map(SourceEvent::Transaction))
+}
+
+fn observation_from_response(source: &str, response: SubscribeTransactionsResponse, received_at: u64) -> Option<Observation> {
+    let transaction_update = response.transaction?;
+    let transaction = transaction_update.transaction?;
+    let signature = transaction.signatures.first()?;
+    
+    Some(Observation {
+        signature: bs58::encode(signature).into_string(),
+        slot: Some(transaction_update.slot),
+        source: source.to_string(),
+        received_at,
+    })
+}
+
+impl StreamSource for ShredlinkClient {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn start(&mut self, tx: mpsc::UnboundedSender<SourceEvent>, epoch: RunEpoch) -> LocalBoxFuture<'_, Result<()>> {
+        Box::pin(self.subscribe(tx, epoch))
+    }
+}
@@ -0,0 +1,250 @@
+//! Per-slot view of the feeds: when each source delivered its first transaction of a slot,
+//! transactions sources place in different slots, and when each source announced each slot status.
+
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+use crate::config::Commitment;
+use crate::histogram::LatencyHistogram;
+
+/// Slot disagreements kept as examples in the report
+const MAX_DISAGREEMENT_EXAMPLES: usize = 20;
+
+/// First-arrival time of each source in every slot (or slot and status) that is still open
+#[derive(Default)]
+pub struct SlotArrivals<K: Eq + Hash = u64> {
+    /// slot -> source -> earliest receive timestamp (ns since run epoch)
+    slots: DashMap<K, HashMap<String, u64>>,
+    /// Recently taken slots -> time they were taken, so late arrivals do not open them again
+    closed: DashMap<K, u64>,
+}
+
+impl<K: Copy + Eq + Hash> SlotArrivals<K> {
+    /// Arrivals for a slot taken within the retention of `forget_closed` are dropped
+    pub fn record(&self, slot: K, source: &str, received_at: u64) {
+        let mut arrivals = match self.slots.entry(slot) {
+            Entry::Occupied(occupied) => occupied.into_ref(),
+            // Checked under the entry lock, as `take_expired` closes a slot before releasing it
+            Entry::Vacant(vacant) => {
+                if self.closed.contains_key(vacant.key()) {
+                    return;
+                }
+                vacant.insert(HashMap::new())
+            }
+        };
+        match arrivals.get_mut(source) {
+            Some(first) => *first = (*first).min(received_at),
+            None => {
+                arrivals.insert(source.to_string(), received_at);
+            }
+        }
+    }
+
+    /// Remove and return the slots whose first transaction arrived before `expired_before`, closing them at `now`
+    pub fn take_expired(&self, expired_before: u64, now: u64) -> Vec<(K, HashMap<String, u64>)> {
+        let expired: Vec<K> = self.slots
+            .iter()
+            .filter(|entry| entry.value().values().min().is_some_and(|&first| first < expired_before))
+            .map(|entry| *entry.key())
+            .collect();
+        
+        expired
+            .into_iter()
+            .filter_map(|slot| match self.slots.entry(slot) {
+                Entry::Occupied(occupied) => {
+                    self.closed.insert(slot, now);
+                    Some(occupied.remove_entry())
+                }
+                Entry::Vacant(_) => None,
+            })
+            .collect()
+    }
+
+    /// Stop remembering slots closed before `closed_before`
+    pub fn forget_closed(&self, closed_before: u64) {
+        self.closed.retain(|_, closed_at| *closed_at >= closed_before);
+    }
+}
+
+/// Per-slot counts of every finalized slot and transaction
+#[derive(Debug, Clone, Default)]
+pub struct SlotTotals {
+    slots: usize,
+    seen: HashMap<String, usize>,
+    first: HashMap<String, usize>,
+    /// Per slot, time from the earliest first arrival of any source to this source's
+    lags: HashMap<String, LatencyHistogram>,
+    disagreements: usize,
+    examples: Vec<SlotDisagreement>,
+}
+
+impl SlotTotals {
+    /// Fold a finished slot; sources within `tie_tolerance_ns` of the earliest are all credited as first
+    pub fn record_slot(&mut self, arrivals: &HashMap<String, u64>, tie_tolerance_ns: i64) {
+        let Some(&earliest) = arrivals.values().min() else {
+            return;
+        };
+        self.slots += 1;
+        
+        for (source, &first) in arrivals {
+            let lag = first - earliest;
+            *self.seen.entry(source.clone()).or_default() += 1;
+            if lag <= tie_tolerance_ns as u64 {
+                *self.first.entry(source.clone()).or_default() += 1;
+            }
+            self.lags
+                .entry(source.clone())
+                .or_insert_with(|| LatencyHistogram::new(0))
+                .record(lag as i64);
+        }
+    }
+
+    /// Count a finished transaction the sources placed in different slots
+    pub fn record_transaction(&mut self, signature: &str, slots: &HashMap<String, u64>) {
+        let mut values = slots.values();
+        let Some(first) = values.next() else {
+            return;
+        };
+        if values.all(|slot| slot == first) {
+            return;
+        }
+        
+        self.disagreements += 1;
+        if self.examples.len() < MAX_DISAGREEMENT_EXAMPLES {
+            self.examples.push(SlotDisagreement {
+                signature: signature.to_string(),
+                slots: slots.iter().map(|(source, &slot)| (source.clone(), slot)).collect(),
+            });
+        }
+    }
+
+    pub fn merge(&mut self, other: &SlotTotals) {
+        self.slots += other.slots;
+        for (source, count) in &other.seen {
+            *self.seen.entry(source.clone()).or_default() += count;
+        }
+        for (source, count) in &other.first {
+            *self.first.entry(source.clone()).or_default() += count;
+        }
+        for (source, lags) in &other.lags {
+            match self.lags.get_mut(source) {
+                Some(existing) => existing.merge(lags),
+                None => {
+                    self.lags.insert(source.clone(), lags.clone());
+                }
+            }
+        }
+        self.disagreements += other.disagreements;
+        let room = MAX_DISAGREEMENT_EXAMPLES.saturating_sub(self.examples.len());
+        self.examples.extend(other.examples.iter().take(room).cloned());
+    }
+
+    pub fn analysis(&self, source_names: &[String]) -> SlotAnalysis {
+        let mut sources: Vec<SlotSourceStats> = source_names
+            .iter()
+            .map(|name| {
+                let lags = self.lags.get(name).cloned().unwrap_or_else(|| LatencyHistogram::new(0));
+                let first = self.first.get(name).copied().unwrap_or(0);
+                
+                SlotSourceStats {
+                    source: name.clone(),
+                    slots_seen: self.seen.get(name).copied().unwrap_or(0),
+                    first_in_slot: first,
+                    first_in_slot_percentage: if self.slots > 0 { first as f64 / self.slots as f64 * 100.0 } else { 0.0 },
+                    median_lag_ms: lags.median() / 1_000_000.0,
+                    p95_lag_ms: lags.percentile(0.95) / 1_000_000.0,
+                    p99_lag_ms: lags.percentile(0.99) / 1_000_000.0,
+                    max_lag_ms: lags.max() as f64 / 1_000_000.0,
+                }
+            })
+            .collect();
+        sources.sort_by(|a, b| a.source.cmp(&b.source));
+        
+        SlotAnalysis {
+            slots: self.slots,
+            sources,
+            disagreements: self.disagreements,
+            disagreement_examples: self.examples.clone(),
+        }
+    }
+}
+
+/// Announcements of every finalized slot status, one `SlotTotals` per status
+#[derive(Debug, Clone, Default)]
+pub struct SlotUpdateTotals {
+    statuses: HashMap<Commitment, SlotTotals>,
+}
+
+impl SlotUpdateTotals {
+    pub fn record(&mut self, status: Commitment, arrivals: &HashMap<String, u64>, tie_tolerance_ns: i64) {
+        self.statuses.entry(status).or_default().record_slot(arrivals, tie_tolerance_ns);
+    }
+
+    pub fn merge(&mut self, other: &SlotUpdateTotals) {
+        for (status, totals) in &other.statuses {
+            self.statuses.entry(*status).or_default().merge(totals);
+        }
+    }
+
+    /// One entry per status that was announced, leaving out sources without slot updates
+    pub fn analysis(&self, source_names: &[String]) -> Vec<SlotStatusAnalysis> {
+        Commitment::ALL
+            .iter()
+            .filter_map(|status| {
+                let analysis = self.statuses.get(status)?.analysis(source_names);
+                
+                Some(SlotStatusAnalysis {
+                    status: *status,
+                    slots: analysis.slots,
+                    sources: analysis.sources.into_iter().filter(|source| source.slots_seen > 0).collect(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// When each source delivered its first transaction of each slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotAnalysis {
+    pub slots: usize,
+    /// Sorted by source name
+    pub sources: Vec<SlotSourceStats>,
+    /// Transactions that sources reported in different slots
+    pub disagreements: usize,
+    pub disagreement_examples: Vec<SlotDisagreement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotSourceStats {
+    pub source: String,
+    /// Slots this source delivered at least one transaction of
+    pub slots_seen: usize,
+    /// Slots this source delivered the first transaction of (within the tie tolerance)
+    pub first_in_slot: usize,
+    pub first_in_slot_percentage: f64,
+    /// Across slots: this source's first arrival in the slot minus the earliest source's
+    pub median_lag_ms: f64,
+    pub p95_lag_ms: f64,
+    pub p99_lag_ms: f64,
+    pub max_lag_ms: f64,
+}
+
+/// A transaction whose slot depends on the source that reported it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotDisagreement {
+    pub signature: String,
+    pub slots: BTreeMap<String, u64>,
+}
+
+/// When each source announced slots reaching one status. `first_in_slot` counts the slots
+/// a source announced first, and the lags are behind the earliest announcement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotStatusAnalysis {
+    pub status: Commitment,
+    pub slots: usize,
+    /// Sorted by source name
+    pub sources: Vec<SlotSourceStats>,
+}
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use futures::future::LocalBoxFuture;
+use std::time::Instant;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::config::Commitment;
+
+/// A single transaction sighting reported by a stream source
+#[derive(Debug, Clone)]
+pub struct Observation {
+    pub signature: String,
+    pub slot: Option<u64>,
+    pub source: String,
+    /// Nanoseconds since the run epoch, taken as soon as the message came off the wire
+    pub received_at: u64,
+}
+
+/// A slot reaching a commitment status, as announced by a source's slot-update stream
+#[derive(Debug, Clone)]
+pub struct SlotUpdate {
+    pub slot: u64,
+    pub status: Commitment,
+    pub source: String,
+    /// Nanoseconds since the run epoch, like `Observation::received_at`
+    pub received_at: u64,
+}
+
+/// Everything a stream source forwards to the benchmark
+#[derive(Debug, Clone)]
+pub enum SourceEvent {
+    Transaction(Observation),
+    Slot(SlotUpdate),
+}
+
+impl SourceEvent {
+    pub fn source(&self) -> &str {
+        match self {
+            SourceEvent::Transaction(observation) => &observation.source,
+            SourceEvent::Slot(update) => &update.source,
+        }
+    }
+
+    pub fn received_at(&self) -> u64 {
+        match self {
+            SourceEvent::Transaction(observation) => observation.received_at,
+            SourceEvent::Slot(update) => update.received_at,
+        }
+    }
+}
+
+/// Common interface for every transaction feed that can take part in a benchmark
+pub trait StreamSource {
+    /// Unique name used to label this source in reports
+    fn name(&self) -> &str;
+
+    /// Connect, subscribe and forward events until the stream ends, stamping them against `epoch`
+    fn start(&mut self, tx: UnboundedSender<SourceEvent>, epoch: RunEpoch) -> LocalBoxFuture<'_, Result<()>>;
+}
+
+/// Monotonic instant every receive timestamp of one run is measured from
+#[derive(Debug, Clone, Copy)]
+pub struct RunEpoch(Instant);
+
+impl RunEpoch {
+    pub fn start() -> Self {
+        Self(Instant::now())
+    }
+
+    pub fn instant(&self) -> Instant {
+        self.0
+    }
+
+    /// Receive timestamp in nanoseconds; clients must call this before any decoding work
+    pub fn timestamp_ns(&self) -> u64 {
+        self.0.elapsed().as_nanos() as u64
+    }
+}
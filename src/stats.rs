@@ -0,0 +1,258 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Binomial, Distribution};
+use serde::{Deserialize, Serialize};
+
+use crate::histogram::LatencyHistogram;
+
+/// Resamples drawn for each bootstrap confidence interval
+const BOOTSTRAP_RESAMPLES: usize = 1000;
+
+/// Fixed seed so the same observations always give the same intervals
+const BOOTSTRAP_SEED: u64 = 0x5eed;
+
+/// z-score of a two-sided 95% interval
+const Z_95: f64 = 1.959964;
+
+/// Two-sided 95% confidence interval
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl ConfidenceInterval {
+    pub fn scaled(self, factor: f64) -> Self {
+        Self { low: self.low * factor, high: self.high * factor }
+    }
+}
+
+/// Percentile bootstrap interval for a statistic of the histogram's values.
+/// Each resample redistributes the same number of values over the recorded buckets
+/// (a multinomial draw), so the cost depends on the bucket count, not the sample size.
+pub fn bootstrap_ci(histogram: &LatencyHistogram, statistic: impl Fn(&[(i64, u64)], u64) -> f64) -> ConfidenceInterval {
+    let len = histogram.len();
+    if len == 0 {
+        return ConfidenceInterval::default();
+    }
+    
+    let buckets = histogram.buckets();
+    let mut rng = StdRng::seed_from_u64(BOOTSTRAP_SEED);
+    let mut sample = buckets.clone();
+    let mut estimates = Vec::with_capacity(BOOTSTRAP_RESAMPLES);
+    
+    for _ in 0..BOOTSTRAP_RESAMPLES {
+        // Multinomial draw as a chain of binomials over the remaining probability mass
+        let mut remaining = len;
+        let mut remaining_weight = len;
+        for (slot, &(_, count)) in sample.iter_mut().zip(&buckets) {
+            let drawn = if remaining == 0 || count >= remaining_weight {
+                remaining
+            } else {
+                Binomial::new(remaining, count as f64 / remaining_weight as f64)
+                    .map(|binomial| binomial.sample(&mut rng))
+                    .unwrap_or(0)
+            };
+            slot.1 = drawn;
+            remaining -= drawn;
+            remaining_weight -= count;
+        }
+        estimates.push(statistic(&sample, len));
+    }
+    
+    estimates.sort_by(f64::total_cmp);
+    let low = ((BOOTSTRAP_RESAMPLES as f64 * 0.025) as usize).min(BOOTSTRAP_RESAMPLES - 1);
+    let high = ((BOOTSTRAP_RESAMPLES as f64 * 0.975) as usize).min(BOOTSTRAP_RESAMPLES - 1);
+    ConfidenceInterval { low: estimates[low], high: estimates[high] }
+}
+
+/// Wilson score interval for a proportion, as a percentage
+pub fn wilson_interval(successes: usize, trials: usize) -> ConfidenceInterval {
+    if trials == 0 {
+        return ConfidenceInterval::default();
+    }
+    
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = Z_95 * Z_95;
+    
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let margin = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+    
+    ConfidenceInterval {
+        low: ((center - margin) * 100.0).max(0.0),
+        high: ((center + margin) * 100.0).min(100.0),
+    }
+}
+
+/// Exact two-sided sign test p-value; ties are left out by the caller
+pub fn sign_test(wins: usize, losses: usize) -> f64 {
+    let n = wins + losses;
+    if n == 0 {
+        return 1.0;
+    }
+    
+    // P(X <= k) for X ~ Binomial(n, 1/2), summed in log space so large n cannot underflow
+    let k = wins.min(losses);
+    let mut log_term = -(n as f64) * std::f64::consts::LN_2;
+    let mut log_sum = log_term;
+    for i in 0..k {
+        log_term += ((n - i) as f64).ln() - ((i + 1) as f64).ln();
+        log_sum = log_add(log_sum, log_term);
+    }
+    
+    (2.0 * log_sum.exp()).min(1.0)
+}
+
+/// Two-sided Wilcoxon signed-rank p-value (normal approximation with tie correction).
+/// Differences within the histogram's tie tolerance are dropped, like ties in the sign test;
+/// values sharing a histogram bucket share their average rank.
+pub fn wilcoxon_signed_rank(histogram: &LatencyHistogram) -> f64 {
+    let tie_tolerance = histogram.tie_tolerance_ns().max(0) as u64;
+    
+    let mut ranked = 0u64;
+    let mut positive_rank_sum = 0.0;
+    let mut tie_correction = 0.0;
+    for (magnitude, positives, negatives) in histogram.signed_magnitudes() {
+        let run = positives + negatives;
+        if magnitude <= tie_tolerance || run == 0 {
+            continue;
+        }
+        
+        // Average rank of the run of equal magnitudes
+        let rank = (2 * ranked + run + 1) as f64 / 2.0;
+        positive_rank_sum += rank * positives as f64;
+        
+        let run = run as f64;
+        tie_correction += run * run * run - run;
+        ranked += positives + negatives;
+    }
+    if ranked == 0 {
+        return 1.0;
+    }
+    
+    let n = ranked as f64;
+    let mean = n * (n + 1.0) / 4.0;
+    let variance = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - tie_correction / 48.0;
+    if variance <= 0.0 {
+        return 1.0;
+    }
+    
+    let deviation = (positive_rank_sum - mean).abs();
+    let z = (deviation - 0.5).max(0.0) / variance.sqrt();
+    // Two-sided tail 2 * (1 - Φ(z)), computed directly so tiny p-values keep their precision
+    erfc(z / std::f64::consts::SQRT_2).min(1.0)
+}
+
+fn log_add(a: f64, b: f64) -> f64 {
+    let (high, low) = if a > b { (a, b) } else { (b, a) };
+    high + (low - high).exp().ln_1p()
+}
+
+/// Complementary error function (Numerical Recipes `erfcc`, relative error below 1.2e-7)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.26551223
+        + t * (1.00002368
+        + t * (0.37409196
+        + t * (0.09678418
+        + t * (-0.18628806
+        + t * (0.27886807
+        + t * (-1.13520398
+        + t * (1.48851587
+        + t * (-0.82215223
+        + t * 0.17087277))))))));
+    let result = t * poly.exp();
+    
+    if x >= 0.0 { result } else { 2.0 - result }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::histogram::histogram_of;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "{} is not within {} of {}", actual, tolerance, expected);
+    }
+
+    #[test]
+    fn wilson_interval_matches_textbook_values() {
+        let interval = wilson_interval(8, 10);
+        assert_close(interval.low, 49.016, 0.001);
+        assert_close(interval.high, 94.332, 0.001);
+        
+        let all = wilson_interval(10, 10);
+        assert_close(all.high, 100.0, 1e-9);
+        assert_close(all.low, 72.247, 0.001);
+        
+        let empty = wilson_interval(0, 0);
+        assert_eq!((empty.low, empty.high), (0.0, 0.0));
+    }
+
+    #[test]
+    fn sign_test_is_exact() {
+        // 2 * P(X <= 1) for X ~ Binomial(10, 1/2) = 2 * 11 / 1024
+        assert_close(sign_test(9, 1), 22.0 / 1024.0, 1e-12);
+        assert_close(sign_test(1, 9), 22.0 / 1024.0, 1e-12);
+        assert_close(sign_test(15, 5), 0.041389465, 1e-9);
+        assert_eq!(sign_test(5, 5), 1.0);
+        assert_eq!(sign_test(0, 0), 1.0);
+        
+        // Summed in log space, so n = 1000 keeps its precision; 2^-1000 alone would underflow
+        assert_close(sign_test(600, 400) / 2.728464156e-10, 1.0, 1e-6);
+        assert_eq!(sign_test(1_000, 1_000), 1.0);
+    }
+
+    #[test]
+    fn erfc_matches_reference_values() {
+        assert_close(erfc(0.0), 1.0, 1e-7);
+        assert_close(erfc(0.5), 0.479500122, 1e-7);
+        assert_close(erfc(1.0), 0.157299207, 1e-7);
+        assert_close(erfc(-1.0), 1.842700793, 1e-7);
+    }
+
+    #[test]
+    fn wilcoxon_of_all_positive_ranks() {
+        // W+ = 55, mean 27.5, variance 96.25, z = (27.5 - 0.5) / sqrt(96.25)
+        let histogram = histogram_of(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10], 0);
+        assert_close(wilcoxon_signed_rank(&histogram), 0.005921537, 1e-6);
+    }
+
+    #[test]
+    fn wilcoxon_of_symmetric_differences_is_one() {
+        let histogram = histogram_of(&[-2, -1, 1, 2], 0);
+        assert_close(wilcoxon_signed_rank(&histogram), 1.0, 1e-7);
+        assert_eq!(wilcoxon_signed_rank(&LatencyHistogram::new(0)), 1.0);
+    }
+
+    #[test]
+    fn wilcoxon_drops_differences_within_the_tie_tolerance() {
+        let histogram = histogram_of(&[1, -2, 3, -4, 5], 5);
+        assert_eq!(wilcoxon_signed_rank(&histogram), 1.0);
+    }
+
+    #[test]
+    fn bootstrap_is_deterministic() {
+        let values: Vec<i64> = (1..=100).collect();
+        let histogram = histogram_of(&values, 0);
+        let median = |buckets: &[(i64, u64)], len: u64| crate::histogram::median_of(buckets, len);
+        
+        let first = bootstrap_ci(&histogram, median);
+        let second = bootstrap_ci(&histogram, median);
+        assert_eq!((first.low, first.high), (second.low, second.high));
+        assert!(first.low <= 50.5 && 50.5 <= first.high);
+        assert!(first.low >= 30.0 && first.high <= 70.0);
+    }
+
+    #[test]
+    fn bootstrap_of_a_constant_sample_is_degenerate() {
+        let histogram = histogram_of(&[7; 50], 0);
+        let interval = bootstrap_ci(&histogram, crate::histogram::median_of);
+        assert_eq!((interval.low, interval.high), (7.0, 7.0));
+        
+        let empty = bootstrap_ci(&LatencyHistogram::new(0), crate::histogram::median_of);
+        assert_eq!((empty.low, empty.high), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,38 @@
+use std::fs;
+use std::process::Command;
+
+/// Two sources that are never connected to: the options are rejected before any client starts
+const SOURCES: &str = "[[sources]]\nname = \"A\"\ntype = \"geyser\"\nurl = \"http://127.0.0.1:1\"\n\
+    [[sources]]\nname = \"B\"\ntype = \"geyser\"\nurl = \"http://127.0.0.1:2\"\n";
+
+/// Runs the benchmark with `run_section` plus two sources as its config
+fn shredlink(name: &str, run_section: &str, args: &[&str]) -> (bool, String) {
+    let config = std::env::temp_dir().join(format!("shredlink-{}-{}.toml", name, std::process::id()));
+    fs::write(&config, format!("{}{}", run_section, SOURCES)).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_shredlink"))
+        .arg("--config")
+        .arg(&config)
+        .args(args)
+        .output()
+        .unwrap();
+    fs::remove_file(&config).unwrap();
+    (output.status.success(), String::from_utf8_lossy(&output.stderr).into_owned())
+}
+
+#[test]
+fn epoch_first_slot_needs_a_schedule() {
+    let (success, stderr) = shredlink("no-schedule", "", &["--epoch-first-slot", "432000"]);
+    
+    assert!(!success);
+    assert!(stderr.contains("need a leader schedule"), "{}", stderr);
+}
+
+#[test]
+fn schedule_from_the_config_accepts_cli_options() {
+    let run_section = "[run]\nleader_schedule = \"missing-schedule.json\"\n";
+    let (success, stderr) = shredlink("schedule", run_section, &["--epoch-first-slot", "432000"]);
+    
+    // Getting as far as reading the schedule means the options were accepted
+    assert!(!success);
+    assert!(stderr.contains("Failed to read leader schedule missing-schedule.json"), "{}", stderr);
+}
@@ -0,0 +1,147 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::time::Instant;
+
+use shredlink_benchmarking::benchmark::{Benchmark, Warmup};
+use shredlink_benchmarking::config::{Commitment, SourceConfig, SourceKind};
+use shredlink_benchmarking::mock::{serve_feed, FeedBehavior, MockFeed, ScriptedTransaction};
+use shredlink_benchmarking::reconnect::ReconnectPolicy;
+use shredlink_benchmarking::source::StreamSource;
+
+/// Arrival differences counted as ties; scripted gaps are at least twice as large
+const TIE_TOLERANCE_MS: u64 = 50;
+
+/// How far a measured latency may be from the scripted one
+const LATENCY_TOLERANCE_MS: f64 = 30.0;
+
+fn assert_latency(name: &str, measured_ms: f64, scripted_ms: f64) {
+    assert!(
+        (measured_ms - scripted_ms).abs() <= LATENCY_TOLERANCE_MS,
+        "{} {:.3}ms is not within {}ms of the scripted {}ms", name, measured_ms, LATENCY_TOLERANCE_MS, scripted_ms
+    );
+}
+
+async fn spawn_feed(kind: SourceKind, script: Arc<Vec<ScriptedTransaction>>, behavior: FeedBehavior, start: Instant) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    
+    let feed = MockFeed::new(script, behavior, start);
+    tokio::spawn(serve_feed(kind, feed, listener));
+    url
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn geyser_lag_matches_script() {
+    // Three warm-up transactions, then ten measured ones. The Geyser feed trails by 200ms
+    // (400ms for one transaction, none for another), far beyond scheduler jitter
+    let mut script: Vec<ScriptedTransaction> = (0..3)
+        .map(|index| ScriptedTransaction::synthetic(index, 99, index * 10))
+        .collect();
+    script.extend((0..10).map(|index| ScriptedTransaction::synthetic(100 + index, 100 + index / 4, 1000 + index * 50)));
+    let dropped = script[3 + 3].signature.clone();
+    let duplicated = script[3 + 5].signature.clone();
+    let slow = script[3 + 7].signature.clone();
+    let tied = script[3 + 9].signature.clone();
+    let script = Arc::new(script);
+    
+    // Leave the clients time to connect before the first transaction
+    let start = Instant::now() + Duration::from_millis(1000);
+    
+    let shredlink_url = spawn_feed(SourceKind::Shredlink, script.clone(), FeedBehavior::default(), start).await;
+    let geyser_url = spawn_feed(
+        SourceKind::Geyser,
+        script.clone(),
+        FeedBehavior {
+            delay_ms: 200,
+            delays_ms: HashMap::from([(slow, 400), (tied, 0)]),
+            drop: HashSet::from([dropped]),
+            duplicate: HashSet::from([duplicated]),
+        },
+        start,
+    ).await;
+    
+    let sources: Vec<Box<dyn StreamSource>> = vec![
+        SourceConfig::new("Shredlink".to_string(), SourceKind::Shredlink, shredlink_url).build(None).unwrap(),
+        SourceConfig::new("Geyser-mock".to_string(), SourceKind::Geyser, geyser_url).build(None).unwrap(),
+    ];
+    let warmup = Warmup { duration: Duration::from_millis(1500), timeout: Duration::from_secs(5) };
+    let mut benchmark = Benchmark::new(sources, "Shredlink".to_string(), ReconnectPolicy::default())
+        .with_warmup(warmup)
+        .with_tie_tolerance(Duration::from_millis(TIE_TOLERANCE_MS))
+        .with_match_window(Duration::from_secs(1));
+    benchmark.run(Duration::from_secs(2)).await.unwrap();
+    
+    let report = benchmark.generate_report();
+    assert_eq!(report.total_transactions, 10);
+    assert_eq!(report.baseline_only_count, 1);
+    assert_eq!((report.missed["Shredlink"], report.missed["Geyser-mock"]), (0, 1));
+    
+    // Measured transactions span slots 100 to 102, and the baseline opens each of them
+    assert_eq!(report.slots.slots, 3);
+    assert_eq!(report.slots.disagreements, 0);
+    let baseline_slots = report.slots.sources.iter().find(|source| source.source == "Shredlink").unwrap();
+    assert_eq!(baseline_slots.first_in_slot, 3);
+    
+    // Only the Geyser feed announces slots: processed 100-102, confirmed 99-101, finalized 98-100
+    assert_eq!(report.slot_updates.len(), 3);
+    for status in &report.slot_updates {
+        assert_eq!(status.slots, 3, "{:?}", status.status);
+        assert_eq!(status.sources.len(), 1);
+        assert_eq!(status.sources[0].source, "Geyser-mock");
+    }
+    
+    let stats = &report.source_results["Geyser-mock"];
+    assert_eq!(stats.matched_transactions, 9);
+    assert_eq!(stats.source_only_count, 0);
+    assert_eq!((stats.baseline_wins, stats.ties, stats.baseline_losses), (8, 1, 0));
+    
+    // Seven transactions trail by 200ms, one by 400ms and one by none
+    assert_latency("min", stats.min_latency_ms, 0.0);
+    assert_latency("median", stats.median_latency_ms, 200.0);
+    assert_latency("average", stats.average_latency_ms, 200.0);
+    assert_latency("max", stats.max_latency_ms, 400.0);
+    
+    // The baseline delivers every shared transaction first, the Geyser feed only the tied one
+    let leaderboard = &report.leaderboard.sources;
+    assert_eq!(leaderboard[0].source, "Shredlink");
+    assert_eq!((leaderboard[0].first_arrivals, leaderboard[1].first_arrivals), (9, 1));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn confirmed_trails_processed_by_one_slot() {
+    // Four transactions per slot, 100ms apart, in slots 100 to 102
+    let script: Vec<ScriptedTransaction> = (0..12)
+        .map(|index| ScriptedTransaction::synthetic(index, 100 + index / 4, index * 100))
+        .collect();
+    let start = Instant::now() + Duration::from_millis(1000);
+    let url = spawn_feed(SourceKind::Geyser, Arc::new(script), FeedBehavior::default(), start).await;
+    
+    // The mock confirms a slot when the next one starts, 100 to 400ms later, and never confirms the last one
+    let sources: Vec<Box<dyn StreamSource>> = [Commitment::Processed, Commitment::Confirmed]
+        .into_iter()
+        .map(|commitment| {
+            let mut source = SourceConfig::new(format!("Geyser@{}", commitment.as_str()), SourceKind::Geyser, url.clone());
+            source.commitment = commitment;
+            source.build(None).unwrap()
+        })
+        .collect();
+    let mut benchmark = Benchmark::new(sources, "Geyser@processed".to_string(), ReconnectPolicy::default())
+        .with_tie_tolerance(Duration::from_millis(TIE_TOLERANCE_MS))
+        .with_match_window(Duration::from_secs(1));
+    benchmark.run(Duration::from_secs(3)).await.unwrap();
+    
+    let report = benchmark.generate_report();
+    assert_eq!(report.total_transactions, 12);
+    assert_eq!(report.missed["Geyser@confirmed"], 4);
+    
+    let stats = &report.source_results["Geyser@confirmed"];
+    assert_eq!(stats.matched_transactions, 8);
+    assert_eq!((stats.baseline_wins, stats.ties, stats.baseline_losses), (8, 0, 0));
+    
+    // In each confirmed slot the transactions wait 400, 300, 200 and 100ms for the next slot
+    assert_latency("min", stats.min_latency_ms, 100.0);
+    assert_latency("median", stats.median_latency_ms, 250.0);
+    assert_latency("max", stats.max_latency_ms, 400.0);
+}
@@ -0,0 +1,52 @@
+use std::fs;
+use std::process::Command;
+use std::time::Duration;
+
+use shredlink_benchmarking::benchmark::{Benchmark, DaemonReports};
+use shredlink_benchmarking::reconnect::ReconnectPolicy;
+use shredlink_benchmarking::report::ReportFormat;
+
+fn shredlink(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_shredlink"))
+        .args(args)
+        .output()
+        .unwrap();
+    (output.status.success(), String::from_utf8_lossy(&output.stderr).into_owned())
+}
+
+#[test]
+fn table_report_cannot_go_to_a_file() {
+    let path = std::env::temp_dir().join(format!("shredlink-table-{}.txt", std::process::id()));
+    let path = path.to_str().unwrap();
+    
+    for args in [vec!["--output", path], vec!["--format", "table", "--output", path]] {
+        let (success, stderr) = shredlink(&args);
+        assert!(!success, "{:?} was accepted", args);
+        assert!(stderr.contains("--output needs --format json, csv or markdown"), "{}", stderr);
+    }
+    assert!(fs::metadata(path).is_err());
+}
+
+#[test]
+fn table_report_cannot_go_to_a_file_from_the_config() {
+    let config = std::env::temp_dir().join(format!("shredlink-table-{}.toml", std::process::id()));
+    fs::write(&config, "[run]\noutput = \"report.txt\"\n").unwrap();
+    let (success, stderr) = shredlink(&["--config", config.to_str().unwrap()]);
+    fs::remove_file(&config).unwrap();
+    
+    assert!(!success);
+    assert!(stderr.contains("--output needs --format json, csv or markdown"), "{}", stderr);
+}
+
+#[tokio::test]
+async fn library_rejects_a_table_file() {
+    let path = std::env::temp_dir().join(format!("shredlink-table-lib-{}.txt", std::process::id()));
+    
+    let benchmark = Benchmark::new(Vec::new(), "Shredlink".to_string(), ReconnectPolicy::default());
+    assert!(benchmark.print_report(ReportFormat::Table, Some(&path)).is_err());
+    
+    let daemon = DaemonReports { interval: Duration::from_secs(1), format: ReportFormat::Table, output: Some(path.clone()) };
+    let mut benchmark = Benchmark::new(Vec::new(), "Shredlink".to_string(), ReconnectPolicy::default()).with_daemon(daemon);
+    assert!(benchmark.run(Duration::from_secs(1)).await.is_err());
+    assert!(fs::metadata(&path).is_err());
+}